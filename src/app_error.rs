@@ -3,16 +3,61 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
+    Json,
 };
+use serde::Serialize;
+
+use crate::game::game_error::GameError;
 
 // Make our own error that wraps `anyhow::Error`.
 #[derive(Debug)]
 pub struct AppError(pub anyhow::Error);
 
+#[derive(Serialize)]
+struct ErrorBody {
+    code: &'static str,
+    message: String,
+}
+
+fn status(err: &GameError) -> StatusCode {
+    match err {
+        GameError::GameNotFound => StatusCode::NOT_FOUND,
+        GameError::NotAuthorized => StatusCode::UNAUTHORIZED,
+        GameError::NotAPlayer => StatusCode::FORBIDDEN,
+        GameError::NotYourTurn
+        | GameError::NotMultiplayer
+        | GameError::SeatTaken
+        | GameError::AlreadyStarted
+        | GameError::WrongPhase
+        | GameError::GameOver
+        | GameError::NoGuessesMade
+        | GameError::CardAlreadyGuessed => StatusCode::CONFLICT,
+        GameError::CardNotFound
+        | GameError::ClueCountTooHigh
+        | GameError::IllegalClue(_)
+        | GameError::InvalidConfig(_)
+        | GameError::UnknownAgent(_)
+        | GameError::StepOutOfRange { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        GameError::ReplayMismatch(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        GameError::AgentRateLimited => StatusCode::TOO_MANY_REQUESTS,
+        GameError::AgentFailed(_) => StatusCode::BAD_GATEWAY,
+    }
+}
+
 // Tell axum how to convert `AppError` into a response.
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, self.0.to_string()).into_response()
+        let (status, code) = match self.0.downcast_ref::<GameError>() {
+            Some(err) => (status(err), err.code()),
+            None => (StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+        };
+
+        let body = ErrorBody {
+            code,
+            message: self.0.to_string(),
+        };
+
+        (status, Json(body)).into_response()
     }
 }
 
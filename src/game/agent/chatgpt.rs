@@ -2,16 +2,19 @@ use async_openai::{
     config::OpenAIConfig,
     types::{
         ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
-        CreateChatCompletionRequestArgs, ResponseFormat,
+        CreateChatCompletionRequestArgs, CreateChatCompletionResponse, ResponseFormat,
     },
     Client,
 };
-use itertools::Itertools;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use crate::game::{
-    agent::utils::board_string,
+    agent::{
+        utils::{board_string, history_string},
+        AgentMove, OperativeAgent, SpymasterAgent,
+    },
+    game_error::GameError,
     game_state::{Clue, GameState, Identity, Team},
 };
 
@@ -34,17 +37,31 @@ struct OpenaiSpymasterResponse {
     associations: Vec<String>,
 }
 
-// TODO: Set GPT version
 pub struct ChatGpt {
     client: Client<OpenAIConfig>,
     team: Team,
+    model: &'static str,
 }
 
+const MAX_CLUE_ATTEMPTS: usize = 3;
+
+const DUET_RULES: &str = r#"This is a cooperative game of Codenames Duet, both sides share one pool of turns.
+Each side has its own key card, the clue giver's key decides what a guess reveals.
+Both sides win together once every agent on both keys is found, and lose together if an assassin is revealed or the turns run out."#;
+
 const OPERATIVE_STEP_1: &str = r#"
 You are an expert player of the game Codenames.
 You are playing as the operative role on the <TEAM> team.
+<MODE>
 Discuss your options and what your guesses should be based on the current game board and clue.
+You must make at least one guess, but you may stop early instead of risking a guess you are unsure about.
+The <STARTING_TEAM> team went first and started with an extra agent.
+The board is a <ROWS>x<COLUMNS> grid:
 <BOARD>
+
+This is what has happened so far:
+<HISTORY>
+
 <CLUE>
 
 The words left to guess are
@@ -57,6 +74,7 @@ You are an agent who distills the guesses from a body of text that discusses of
 Summarize the following into a JSON array of guesses:
 <CHAIN>
 
+Only include the guesses the discussion decided to make, leave out any it chose not to risk.
 The format of the response should be an array of guesses with justification in order of priority:
 
 ```json
@@ -74,11 +92,21 @@ The format of the response should be an array of guesses with justification in o
 const SPYMASTER_STEP_1: &str = r#"
 You are an expert player of the game Codenames.
 You are playing as the spymaster role for the <TEAM> team.
+<MODE>
 Discuss your options and what would be the best clue based on the current game board.
+The <STARTING_TEAM> team went first and started with an extra agent.
+The board is a <ROWS>x<COLUMNS> grid:
 <BOARD>
 
+This is what has happened so far:
+<HISTORY>
+
 The remaining cards you are trying to get your operative to guess are:
 <REMAINING>
+
+You have <AGENTS_LEFT> agents left to find, the number in your clue can not be higher than that.
+The clue must be a single word that is not, does not contain, is not part of and does not share a stem with any unrevealed word on the board.
+<REJECTED>
 "#;
 
 const SPYMASTER_STEP_2: &str = r#"
@@ -99,30 +127,56 @@ The format of the response should be a JSON object of the following format
 ```
 "#;
 
+fn mode_rules(game_state: &GameState) -> String {
+    match game_state.is_duet() {
+        true => DUET_RULES.to_string(),
+        false => game_state.config().house_rules().describe().join("\n"),
+    }
+}
+
+/// Text of the first choice
+fn first_choice(response: CreateChatCompletionResponse) -> Result<String, GameError> {
+    response
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .ok_or_else(|| GameError::AgentFailed("Empty response".to_string()))
+}
+
 impl ChatGpt {
-    pub fn new(team: Team) -> Self {
+    pub fn new(team: Team, model: &'static str) -> Self {
         Self {
             client: Client::new(),
             team,
+            model,
         }
     }
 
-    pub async fn try_gen_guesses(&self, game_state: &GameState) -> Option<Vec<String>> {
+    async fn gen_guesses(&self, game_state: &GameState) -> Result<Option<Vec<String>>, GameError> {
         tracing::info!("Openai Operative making guess");
 
-        let clue = format!("{:?}", game_state.clue().unwrap());
+        let clue = format!("The clue is {}", game_state.clue().unwrap());
         let hidden_board = game_state.to_hidden_board();
-        let board = board_string(&hidden_board);
+        let config = game_state.config();
+        let board = board_string(&hidden_board, config.columns() as usize);
         let remaining_cards = hidden_board
             .into_iter()
-            .filter(|card| card.identity() == &Identity::Hidden)
+            .filter(|card| {
+                card.identity() == &Identity::Hidden && !card.bystander_for().contains(&self.team)
+            })
             .map(|card| card.word().to_string())
             .collect::<Vec<String>>()
             .join(", ");
 
         let system_prompt = OPERATIVE_STEP_1
             .replace("<TEAM>", &self.team.to_string())
+            .replace("<MODE>", &mode_rules(game_state))
+            .replace("<ROWS>", &config.rows().to_string())
+            .replace("<COLUMNS>", &config.columns().to_string())
+            .replace("<STARTING_TEAM>", &game_state.starting_team().to_string())
             .replace("<BOARD>", &board)
+            .replace("<HISTORY>", &history_string(game_state.events()))
             .replace("<CLUE>", &clue)
             .replace("<REMAINING>", &remaining_cards);
 
@@ -137,21 +191,13 @@ impl ChatGpt {
 
         let request = CreateChatCompletionRequestArgs::default()
             .max_tokens(512u16)
-            .model("gpt-4o")
+            .model(self.model)
             .messages(messages)
             .build()
             .unwrap();
 
-        let openai_response = self.client.chat().create(request).await.unwrap();
-
-        let response_content = openai_response
-            .choices
-            .first()
-            .unwrap()
-            .message
-            .content
-            .clone()
-            .unwrap();
+        let openai_response = self.client.chat().create(request).await?;
+        let response_content = first_choice(openai_response)?;
 
         // tracing::debug!("Openai Operative response 1: {response_content}");
 
@@ -168,62 +214,100 @@ impl ChatGpt {
 
         let request = CreateChatCompletionRequestArgs::default()
             .max_tokens(512u16)
-            .model("gpt-4o")
+            .model(self.model)
             .messages(messages)
             .response_format(ResponseFormat::JsonObject)
             .build()
             .unwrap();
 
-        let openai_response = self.client.chat().create(request).await.unwrap();
-
-        let response_content = openai_response
-            .choices
-            .first()
-            .unwrap()
-            .message
-            .content
-            .clone()
-            .unwrap();
+        let openai_response = self.client.chat().create(request).await?;
+        let response_content = first_choice(openai_response)?;
 
         // tracing::info!("Openai Operative second 2: {system_prompt}");
 
         let re = Regex::new(r"\[[^\]]*\]").unwrap();
 
         let json_guesses = re
-            .captures(&response_content)
-            .unwrap()
-            .get(0)
-            .unwrap()
+            .find(&response_content)
+            .ok_or_else(|| GameError::AgentFailed("No JSON in the response".to_string()))?
             .as_str()
             .to_string();
 
         tracing::info!("Openai Operative Guesses: {json_guesses}");
 
+        let allowed_guesses = game_state
+            .clue()
+            .and_then(|clue| clue.remaining())
+            .map_or(usize::MAX, usize::from);
+
         let guesses = serde_json::from_str::<OpenaiOperativeResponse>(&json_guesses)
-            .unwrap()
+            .map_err(|err| GameError::AgentFailed(err.to_string()))?
             .into_iter()
             .map(|guess| guess.guess)
+            .take(allowed_guesses)
             .collect();
 
         tracing::debug!("Guess: {:?}", guesses);
-        Some(guesses)
+        Ok(Some(guesses))
+    }
+
+    async fn gen_clue(&self, game_state: &GameState) -> Result<Option<Clue>, GameError> {
+        let mut rejected = Vec::new();
+
+        for _ in 0..MAX_CLUE_ATTEMPTS {
+            let clue = self.request_clue(game_state, &rejected).await?;
+
+            match game_state.check_clue(&clue) {
+                Ok(()) => return Ok(Some(clue)),
+                Err(err @ (GameError::IllegalClue(_) | GameError::ClueCountTooHigh)) => {
+                    tracing::warn!("Openai Spymaster illegal clue: {err}");
+                    rejected.push(format!("{} {}: {}", clue.word(), clue.count(), err));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        tracing::warn!("Openai Spymaster could not come up with a legal clue");
+        Err(GameError::AgentFailed(
+            "Could not come up with a legal clue".to_string(),
+        ))
     }
 
-    pub async fn try_gen_clue(&self, game_state: &GameState) -> Option<Clue> {
+    async fn request_clue(
+        &self,
+        game_state: &GameState,
+        rejected: &[String],
+    ) -> Result<Clue, GameError> {
         tracing::info!("Openai Spymaster creating clue");
 
-        let board = board_string(game_state.board());
-        let remaining_cards: String = game_state
-            .board()
+        let rejected = match rejected.is_empty() {
+            true => String::new(),
+            false => format!(
+                "These clues were already rejected, do not give them again:\n{}",
+                rejected.join("\n")
+            ),
+        };
+
+        let config = game_state.config();
+        let side_board = game_state.to_side_board(&self.team);
+        let board = board_string(&side_board, config.columns() as usize);
+        let remaining_cards: Vec<&str> = side_board
             .iter()
             .filter(|card| card.identity() == &self.team && !card.guessed())
             .map(|card| card.word())
-            .join(", ");
+            .collect();
 
         let system_prompt = SPYMASTER_STEP_1
             .replace("<TEAM>", &self.team.to_string())
+            .replace("<MODE>", &mode_rules(game_state))
+            .replace("<ROWS>", &config.rows().to_string())
+            .replace("<COLUMNS>", &config.columns().to_string())
+            .replace("<STARTING_TEAM>", &game_state.starting_team().to_string())
             .replace("<BOARD>", &board)
-            .replace("<REMAINING>", &remaining_cards);
+            .replace("<HISTORY>", &history_string(game_state.events()))
+            .replace("<REMAINING>", &remaining_cards.join(", "))
+            .replace("<AGENTS_LEFT>", &remaining_cards.len().to_string())
+            .replace("<REJECTED>", &rejected);
 
         tracing::info!("Openai Spymaster first prompt: {system_prompt}");
 
@@ -236,21 +320,13 @@ impl ChatGpt {
 
         let request = CreateChatCompletionRequestArgs::default()
             .max_tokens(512u16)
-            .model("gpt-4o")
+            .model(self.model)
             .messages(messages)
             .build()
             .unwrap();
 
-        let openai_response = self.client.chat().create(request).await.unwrap();
-
-        let response_content = openai_response
-            .choices
-            .first()
-            .unwrap()
-            .message
-            .content
-            .clone()
-            .unwrap();
+        let openai_response = self.client.chat().create(request).await?;
+        let response_content = first_choice(openai_response)?;
 
         // tracing::debug!("Openai Spymaster response 1: {response_content}");
 
@@ -267,42 +343,45 @@ impl ChatGpt {
 
         let request = CreateChatCompletionRequestArgs::default()
             .max_tokens(512u16)
-            .model("gpt-4o")
+            .model(self.model)
             .messages(messages)
             .response_format(ResponseFormat::JsonObject)
             .build()
             .unwrap();
 
-        let openai_response = self.client.chat().create(request).await.unwrap();
-
-        let response_content = openai_response
-            .choices
-            .first()
-            .unwrap()
-            .message
-            .content
-            .clone()
-            .unwrap();
+        let openai_response = self.client.chat().create(request).await?;
+        let response_content = first_choice(openai_response)?;
 
         // tracing::info!("Openai Spymaster second 2: {system_prompt}");
 
         let re = Regex::new(r"\{[^\}]*\}").unwrap();
 
         let json_guesses = re
-            .captures(&response_content)
-            .unwrap()
-            .get(0)
-            .unwrap()
+            .find(&response_content)
+            .ok_or_else(|| GameError::AgentFailed("No JSON in the response".to_string()))?
             .as_str()
             .to_string();
 
         // tracing::info!("Openai Spymaster Guesses: {json_guesses}");
-        let clue: OpenaiSpymasterResponse = serde_json::from_str(&json_guesses).unwrap();
+        let clue: OpenaiSpymasterResponse = serde_json::from_str(&json_guesses)
+            .map_err(|err| GameError::AgentFailed(err.to_string()))?;
 
         tracing::debug!("Clue Justifications: {clue:?}");
 
-        let clue = Clue::new(clue.word, clue.number);
+        let clue = Clue::new(clue.word, clue.number.into());
         tracing::info!("Openai Spymaster Clue: {clue:?}");
-        Some(clue)
+        Ok(clue)
+    }
+}
+
+impl OperativeAgent for ChatGpt {
+    fn try_gen_guesses<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Vec<String>> {
+        Box::pin(self.gen_guesses(game_state))
+    }
+}
+
+impl SpymasterAgent for ChatGpt {
+    fn try_gen_clue<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Clue> {
+        Box::pin(self.gen_clue(game_state))
     }
 }
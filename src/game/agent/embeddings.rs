@@ -0,0 +1,329 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::game::{
+    agent::{AgentMove, OperativeAgent, SpymasterAgent},
+    clue_validator::validate_clue,
+    game_error::GameError,
+    game_state::{Card, Clue, ClueCount, GameState, Identity, Team},
+};
+
+/// Only the most frequent words are considered as clues, rarer ones make for obscure clues
+const CLUE_CANDIDATES: usize = 20_000;
+const MAX_TARGETS: usize = 3;
+/// How much closer to the clue every targeted card must be than the cards to avoid
+const MARGIN: f32 = 0.05;
+const ASSASSIN_MARGIN: f32 = 0.1;
+const MIN_SIMILARITY: f32 = 0.25;
+/// Operatives stop guessing once the next card is less similar than this to the clue
+const GUESS_THRESHOLD: f32 = 0.3;
+
+/// Unit length word vectors from a GloVe or word2vec text file, most frequent words first
+pub struct Embeddings {
+    words: Vec<String>,
+    vectors: Vec<Vec<f32>>,
+    index: HashMap<String, usize>,
+}
+
+impl Embeddings {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+
+        let mut embeddings = Embeddings {
+            words: Vec::new(),
+            vectors: Vec::new(),
+            index: HashMap::new(),
+        };
+        let mut dimensions = None;
+
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            let mut parts = line.split_whitespace();
+            let Some(word) = parts.next() else {
+                continue;
+            };
+
+            let mut vector = parts
+                .map(str::parse)
+                .collect::<Result<Vec<f32>, _>>()
+                .with_context(|| format!("Invalid vector on line {}", number + 1))?;
+
+            // word2vec files start with a header of the word count and dimensions
+            if vector.len() < 2 {
+                continue;
+            }
+
+            match dimensions {
+                None => dimensions = Some(vector.len()),
+                Some(dimensions) if dimensions != vector.len() => bail!(
+                    "Line {} has {} dimensions instead of {}",
+                    number + 1,
+                    vector.len(),
+                    dimensions
+                ),
+                Some(_) => {}
+            }
+
+            let word = word.to_lowercase();
+            if embeddings.index.contains_key(&word) {
+                continue;
+            }
+
+            normalize(&mut vector);
+            embeddings
+                .index
+                .insert(word.clone(), embeddings.words.len());
+            embeddings.words.push(word);
+            embeddings.vectors.push(vector);
+        }
+
+        if embeddings.words.is_empty() {
+            bail!("No word vectors in {}", path.display());
+        }
+
+        tracing::info!(
+            "Loaded {} word vectors from {}",
+            embeddings.words.len(),
+            path.display()
+        );
+        Ok(embeddings)
+    }
+
+    /// Vector for a board word, phrases like "ice cream" average the words they are made of
+    fn vector(&self, word: &str) -> Option<Vec<f32>> {
+        let word = word.trim().to_lowercase();
+        if let Some(&index) = self.index.get(&word) {
+            return Some(self.vectors[index].clone());
+        }
+
+        let parts: Vec<&Vec<f32>> = word
+            .split(|c: char| !c.is_alphabetic())
+            .filter_map(|part| self.index.get(part))
+            .map(|&index| &self.vectors[index])
+            .collect();
+
+        let first = parts.first()?;
+        let mut vector = vec![0.0; first.len()];
+        for part in parts {
+            for (sum, value) in vector.iter_mut().zip(part) {
+                *sum += value;
+            }
+        }
+
+        normalize(&mut vector);
+        Some(vector)
+    }
+}
+
+fn normalize(vector: &mut [f32]) {
+    let length = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if length > 0.0 {
+        vector.iter_mut().for_each(|value| *value /= length);
+    }
+}
+
+/// Cosine similarity of two unit length vectors
+fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Gives clues without a network connection, by word vector similarity to the board
+pub struct EmbeddingSpymaster {
+    embeddings: Arc<Embeddings>,
+    team: Team,
+}
+
+impl EmbeddingSpymaster {
+    pub fn new(embeddings: Arc<Embeddings>, team: Team) -> Self {
+        Self { embeddings, team }
+    }
+}
+
+/// Picks the clue that clearly points at the most of the team's cards
+///
+/// Falls back to the clue with the widest margin to a single card when none clears the margins.
+fn pick_clue(embeddings: &Embeddings, team: &Team, board: &[Card]) -> Option<Clue> {
+    let mut own = Vec::new();
+    let mut assassins = Vec::new();
+    let mut others = Vec::new();
+
+    for card in board
+        .iter()
+        .filter(|card| !card.guessed() && !card.bystander_for().contains(team))
+    {
+        let Some(vector) = embeddings.vector(card.word()) else {
+            tracing::debug!("No word vector for {}", card.word());
+            continue;
+        };
+
+        match card.identity() {
+            Identity::DoubleAgent => own.push(vector),
+            identity if identity == team => own.push(vector),
+            Identity::Assassin => assassins.push(vector),
+            _ => others.push(vector),
+        }
+    }
+
+    let closest = |vectors: &[Vec<f32>], candidate: &[f32]| {
+        vectors
+            .iter()
+            .map(|vector| similarity(vector, candidate))
+            .fold(f32::MIN, f32::max)
+    };
+
+    let mut best: Option<(usize, f32, &str)> = None;
+    let mut fallback: Option<(f32, &str)> = None;
+
+    let candidates = embeddings.words.iter().zip(&embeddings.vectors);
+    for (word, candidate) in candidates.take(CLUE_CANDIDATES) {
+        if word.len() < 3 || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+            continue;
+        }
+
+        let mut targets: Vec<f32> = own
+            .iter()
+            .map(|vector| similarity(vector, candidate))
+            .collect();
+        targets.sort_by(|a, b| b.total_cmp(a));
+
+        let assassin = closest(&assassins, candidate);
+        let other = closest(&others, candidate);
+        let threshold = (assassin + ASSASSIN_MARGIN)
+            .max(other + MARGIN)
+            .max(MIN_SIMILARITY);
+
+        let count = targets
+            .iter()
+            .take(MAX_TARGETS)
+            .take_while(|similarity| **similarity > threshold)
+            .count();
+        let total: f32 = targets.iter().take(count).sum();
+
+        let better = match best {
+            _ if count == 0 => false,
+            None => true,
+            Some((best_count, best_total, _)) => (count, total) > (best_count, best_total),
+        };
+
+        if better && validate_clue(word, board).is_ok() {
+            best = Some((count, total, word));
+        }
+
+        let Some(&top) = targets.first() else {
+            continue;
+        };
+        let margin = top - assassin.max(other);
+        if fallback.is_none_or(|(best_margin, _)| margin > best_margin)
+            && validate_clue(word, board).is_ok()
+        {
+            fallback = Some((margin, word));
+        }
+    }
+
+    let (count, word) = match (best, fallback) {
+        (Some((count, _, word)), _) => (count, word),
+        (None, Some((_, word))) => (1, word),
+        (None, None) => return None,
+    };
+
+    Some(Clue::new(word.to_string(), ClueCount::from(count as u8)))
+}
+
+impl SpymasterAgent for EmbeddingSpymaster {
+    fn try_gen_clue<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Clue> {
+        let embeddings = self.embeddings.clone();
+        let team = self.team.clone();
+        let board = game_state.to_side_board(&self.team);
+
+        Box::pin(async move {
+            let clue = tokio::task::spawn_blocking(move || pick_clue(&embeddings, &team, &board))
+                .await
+                .map_err(|err| GameError::AgentFailed(err.to_string()))?
+                .ok_or_else(|| {
+                    GameError::AgentFailed("No word vectors for the cards left".to_string())
+                })?;
+
+            tracing::info!("Embedding Spymaster Clue: {clue:?}");
+            Ok(Some(clue))
+        })
+    }
+}
+
+/// Guesses without a network connection, by word vector similarity to the clue
+pub struct EmbeddingOperative {
+    embeddings: Arc<Embeddings>,
+    team: Team,
+}
+
+impl EmbeddingOperative {
+    pub fn new(embeddings: Arc<Embeddings>, team: Team) -> Self {
+        Self { embeddings, team }
+    }
+}
+
+/// Always makes the closest guess, then keeps going while the cards stay close to the clue
+fn pick_guesses(
+    embeddings: &Embeddings,
+    team: &Team,
+    game_state: &GameState,
+) -> Result<Vec<String>, GameError> {
+    let Some(clue) = game_state.clue() else {
+        return Err(GameError::WrongPhase);
+    };
+
+    let Some(clue_vector) = embeddings.vector(clue.word()) else {
+        return Err(GameError::AgentFailed(format!(
+            "No word vector for the clue {}",
+            clue.word()
+        )));
+    };
+
+    let mut ranked: Vec<(f32, &str)> = game_state
+        .board()
+        .iter()
+        .filter(|card| !card.guessed() && !card.bystander_for().contains(team))
+        .filter_map(|card| {
+            let vector = embeddings.vector(card.word())?;
+            Some((similarity(&vector, &clue_vector), card.word()))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let allowed_guesses = clue.remaining().map_or(usize::MAX, usize::from);
+    let guesses = ranked
+        .iter()
+        .enumerate()
+        .take_while(|(rank, (similarity, _))| *rank == 0 || *similarity >= GUESS_THRESHOLD)
+        .map(|(_, (_, word))| word.to_string())
+        .take(allowed_guesses)
+        .collect();
+
+    Ok(guesses)
+}
+
+impl OperativeAgent for EmbeddingOperative {
+    fn try_gen_guesses<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Vec<String>> {
+        let embeddings = self.embeddings.clone();
+        let team = self.team.clone();
+        let game_state = game_state.clone();
+
+        Box::pin(async move {
+            let guesses =
+                tokio::task::spawn_blocking(move || pick_guesses(&embeddings, &team, &game_state))
+                    .await
+                    .map_err(|err| GameError::AgentFailed(err.to_string()))??;
+
+            tracing::info!("Embedding Operative Guesses: {guesses:?}");
+            Ok(Some(guesses))
+        })
+    }
+}
@@ -0,0 +1,151 @@
+use rand::{rngs::StdRng, seq::IndexedRandom, SeedableRng};
+use serde::Deserialize;
+
+use crate::game::{
+    agent::{AgentMove, OperativeAgent, SpymasterAgent},
+    clue_validator::validate_clue,
+    game_error::GameError,
+    game_event::EventKind,
+    game_state::{Clue, ClueCount, GameState, Seat, Team},
+};
+
+/// Clues the random spymaster picks from, none of them are in the word bank
+const CLUE_WORDS: [&str; 12] = [
+    "alfa", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliett",
+    "kilo", "lima",
+];
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ScriptedClue {
+    word: String,
+    count: ClueCount,
+}
+
+/// Clues the team has given so far, which is also the turn the script is on
+fn clues_given(game_state: &GameState, team: &Team) -> usize {
+    let spymaster = Seat::spymaster(team);
+    game_state
+        .events()
+        .iter()
+        .filter(|event| {
+            event.seat() == &spymaster && matches!(event.kind(), EventKind::ClueGiven { .. })
+        })
+        .count()
+}
+
+/// Every scripted guess has to name a card, and the first must still be hidden so a guess is made
+fn check_guesses(game_state: &GameState, guesses: &[String]) -> Result<(), GameError> {
+    let card = |guess: &str| {
+        game_state
+            .board()
+            .iter()
+            .find(|card| card.word().eq_ignore_ascii_case(guess.trim()))
+    };
+
+    if let Some(guess) = guesses.iter().find(|guess| card(guess).is_none()) {
+        return Err(GameError::AgentFailed(format!(
+            "Scripted guess {} is not on the board",
+            guess
+        )));
+    }
+
+    match guesses.first().and_then(|guess| card(guess)) {
+        Some(card) if !card.guessed() => Ok(()),
+        _ => Err(GameError::AgentFailed(
+            "Scripted guesses must start with a hidden card".to_string(),
+        )),
+    }
+}
+
+/// Seeded from the game and its progress, so replaying a game makes the same choices
+fn rng(game_state: &GameState, team: &Team) -> StdRng {
+    let seed = game_state.seed().unwrap_or_default();
+    let progress = game_state.events().len() as u64;
+    StdRng::seed_from_u64(seed ^ (progress << 1) ^ (*team == Team::Red) as u64)
+}
+
+/// Plays its script, then gives one card clues chosen at random
+pub struct MockSpymaster {
+    team: Team,
+    script: Vec<ScriptedClue>,
+}
+
+impl MockSpymaster {
+    pub fn new(team: Team, script: Vec<ScriptedClue>) -> Self {
+        Self { team, script }
+    }
+
+    fn pick_clue(&self, game_state: &GameState) -> Result<Clue, GameError> {
+        if let Some(clue) = self.script.get(clues_given(game_state, &self.team)) {
+            let clue = Clue::new(clue.word.clone(), clue.count.clone());
+            game_state.check_clue(&clue).map_err(|err| {
+                GameError::AgentFailed(format!("Scripted clue {} is illegal: {}", clue.word(), err))
+            })?;
+            return Ok(clue);
+        }
+
+        let valid: Vec<&str> = CLUE_WORDS
+            .into_iter()
+            .filter(|word| validate_clue(word, game_state.board()).is_ok())
+            .collect();
+
+        let word = valid
+            .choose(&mut rng(game_state, &self.team))
+            .ok_or_else(|| GameError::AgentFailed("No clue word left to give".to_string()))?;
+
+        Ok(Clue::new(word.to_string(), ClueCount::Number(1)))
+    }
+}
+
+impl SpymasterAgent for MockSpymaster {
+    fn try_gen_clue<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Clue> {
+        Box::pin(async move {
+            let clue = self.pick_clue(game_state)?;
+            tracing::info!("Mock Spymaster Clue: {clue:?}");
+            Ok(Some(clue))
+        })
+    }
+}
+
+/// Plays its script, then guesses one card at random each turn
+pub struct MockOperative {
+    team: Team,
+    script: Vec<Vec<String>>,
+}
+
+impl MockOperative {
+    pub fn new(team: Team, script: Vec<Vec<String>>) -> Self {
+        Self { team, script }
+    }
+
+    fn pick_guesses(&self, game_state: &GameState) -> Result<Vec<String>, GameError> {
+        let turn = clues_given(game_state, &self.team).saturating_sub(1);
+        if let Some(guesses) = self.script.get(turn) {
+            check_guesses(game_state, guesses)?;
+            return Ok(guesses.clone());
+        }
+
+        let hidden: Vec<&str> = game_state
+            .board()
+            .iter()
+            .filter(|card| !card.guessed() && !card.bystander_for().contains(&self.team))
+            .map(|card| card.word())
+            .collect();
+
+        let guess = hidden
+            .choose(&mut rng(game_state, &self.team))
+            .ok_or_else(|| GameError::AgentFailed("No card left to guess".to_string()))?;
+
+        Ok(vec![guess.to_string()])
+    }
+}
+
+impl OperativeAgent for MockOperative {
+    fn try_gen_guesses<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Vec<String>> {
+        Box::pin(async move {
+            let guesses = self.pick_guesses(game_state)?;
+            tracing::info!("Mock Operative Guesses: {guesses:?}");
+            Ok(Some(guesses))
+        })
+    }
+}
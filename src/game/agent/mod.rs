@@ -1,95 +1,167 @@
-use self::{chatgpt::ChatGpt, player::Player};
+use std::{future::Future, pin::Pin, sync::Arc};
 
-use super::{game_controller::Role, game_state::Team};
+use serde::Deserialize;
+
+use self::{
+    mock::{MockOperative, MockSpymaster, ScriptedClue},
+    player::Player,
+    registry::AgentRegistry,
+};
+
+use super::{
+    game_error::GameError,
+    game_state::{Clue, GameState, Seat, Team},
+};
 
 pub mod chatgpt;
+pub mod embeddings;
+pub mod mock;
 pub mod player;
+pub mod registry;
 mod utils;
 
-pub enum Operative {
-    Player(Player),
-    ChatGpt(ChatGpt),
-}
+/// An agent's next move, None when the seat waits on a human instead
+pub type AgentMove<'a, T> = Pin<Box<dyn Future<Output = Result<Option<T>, GameError>> + Send + 'a>>;
 
-impl Operative {
-    pub fn is_player(&self) -> bool {
-        match self {
-            Self::Player(_) => true,
-            Self::ChatGpt(_) => false,
-        }
+pub trait OperativeAgent: Send + Sync {
+    fn is_player(&self) -> bool {
+        false
     }
 
-    pub async fn try_gen_guesses(
-        &self,
-        game_state: &super::game_state::GameState,
-    ) -> Option<Vec<String>> {
-        match self {
-            Self::Player(player) => player.try_gen_guesses(game_state).await,
-            Self::ChatGpt(chatgpt) => chatgpt.try_gen_guesses(game_state).await,
-        }
+    /// Guesses for the current clue in the order they should be made
+    fn try_gen_guesses<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Vec<String>>;
+}
+
+pub trait SpymasterAgent: Send + Sync {
+    fn is_player(&self) -> bool {
+        false
     }
+
+    fn try_gen_clue<'a>(&'a self, game_state: &'a GameState) -> AgentMove<'a, Clue>;
 }
 
-pub enum Spymaster {
-    Player(Player),
-    ChatGpt(ChatGpt),
+/// How a seat is played, by a registered agent or from a script of moves
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AgentChoice {
+    Named(String),
+    /// One clue per turn, seeded random clues once they run out
+    Clues {
+        clues: Vec<ScriptedClue>,
+    },
+    /// The guesses for each turn, seeded random guesses once they run out
+    Guesses {
+        guesses: Vec<Vec<String>>,
+    },
 }
 
-impl Spymaster {
-    pub fn is_player(&self) -> bool {
-        match self {
-            Self::Player(_) => true,
-            Self::ChatGpt(_) => false,
+/// Which agent plays each seat, unnamed seats get the registry's default agent
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SeatAgents {
+    #[serde(rename = "redOperative")]
+    red_operative: Option<AgentChoice>,
+    #[serde(rename = "redSpymaster")]
+    red_spymaster: Option<AgentChoice>,
+    #[serde(rename = "blueOperative")]
+    blue_operative: Option<AgentChoice>,
+    #[serde(rename = "blueSpymaster")]
+    blue_spymaster: Option<AgentChoice>,
+}
+
+impl SeatAgents {
+    fn choice(&self, seat: &Seat) -> Option<&AgentChoice> {
+        match seat {
+            Seat::RedOperative => self.red_operative.as_ref(),
+            Seat::RedSpymaster => self.red_spymaster.as_ref(),
+            Seat::BlueOperative => self.blue_operative.as_ref(),
+            Seat::BlueSpymaster => self.blue_spymaster.as_ref(),
         }
     }
 
-    pub async fn try_gen_clue(
+    fn operative(
         &self,
-        game_state: &super::game_state::GameState,
-    ) -> Option<super::game_state::Clue> {
-        match self {
-            Self::Player(player) => player.try_gen_clue(game_state).await,
-            Self::ChatGpt(chatgpt) => chatgpt.try_gen_clue(game_state).await,
+        registry: &AgentRegistry,
+        team: Team,
+    ) -> Result<Box<dyn OperativeAgent>, GameError> {
+        let seat = Seat::operative(&team);
+        match self.choice(&seat) {
+            None => registry.operative(registry.default_agent(), team),
+            Some(AgentChoice::Named(name)) => registry.operative(name, team),
+            Some(AgentChoice::Guesses { guesses }) => {
+                Ok(Box::new(MockOperative::new(team, guesses.clone())))
+            }
+            Some(AgentChoice::Clues { .. }) => Err(GameError::InvalidConfig(format!(
+                "{:?} is an operative and cannot be given clues",
+                seat
+            ))),
+        }
+    }
+
+    fn spymaster(
+        &self,
+        registry: &AgentRegistry,
+        team: Team,
+    ) -> Result<Box<dyn SpymasterAgent>, GameError> {
+        let seat = Seat::spymaster(&team);
+        match self.choice(&seat) {
+            None => registry.spymaster(registry.default_agent(), team),
+            Some(AgentChoice::Named(name)) => registry.spymaster(name, team),
+            Some(AgentChoice::Clues { clues }) => {
+                Ok(Box::new(MockSpymaster::new(team, clues.clone())))
+            }
+            Some(AgentChoice::Guesses { .. }) => Err(GameError::InvalidConfig(format!(
+                "{:?} is a spymaster and cannot be given guesses",
+                seat
+            ))),
         }
     }
 }
 
+/// Shared so an agent can think in its own task while the game keeps taking requests
 pub struct Agents {
-    pub red_operative: Operative,
-    pub blue_operative: Operative,
-    pub red_spymaster: Spymaster,
-    pub blue_spymaster: Spymaster,
+    pub red_operative: Arc<dyn OperativeAgent>,
+    pub blue_operative: Arc<dyn OperativeAgent>,
+    pub red_spymaster: Arc<dyn SpymasterAgent>,
+    pub blue_spymaster: Arc<dyn SpymasterAgent>,
 }
 
 impl Agents {
-    pub fn new(role: Role) -> Self {
-        let (red_operative, red_spymaster): (Operative, Spymaster) = match role {
-            Role::RedOperative => (
-                Operative::Player(Player),
-                Spymaster::ChatGpt(ChatGpt::new(Team::Red)),
-            ),
-            Role::RedSpymaster => (
-                Operative::ChatGpt(ChatGpt::new(Team::Red)),
-                Spymaster::Player(Player),
-            ),
+    /// Players take the given seats and the chosen agents the rest
+    pub fn new(
+        registry: &AgentRegistry,
+        choices: &SeatAgents,
+        seats: &[Seat],
+    ) -> Result<Self, GameError> {
+        let operative = |team: Team| {
+            let seat = Seat::operative(&team);
+            match seats.contains(&seat) {
+                true => Ok(Box::new(Player) as Box<dyn OperativeAgent>),
+                false => choices.operative(registry, team),
+            }
+        };
+        let spymaster = |team: Team| {
+            let seat = Seat::spymaster(&team);
+            match seats.contains(&seat) {
+                true => Ok(Box::new(Player) as Box<dyn SpymasterAgent>),
+                false => choices.spymaster(registry, team),
+            }
         };
 
-        Self {
-            red_operative,
-            red_spymaster,
-            blue_operative: Operative::ChatGpt(ChatGpt::new(Team::Blue)),
-            blue_spymaster: Spymaster::ChatGpt(ChatGpt::new(Team::Blue)),
-        }
+        Ok(Self {
+            red_operative: operative(Team::Red)?.into(),
+            red_spymaster: spymaster(Team::Red)?.into(),
+            blue_operative: operative(Team::Blue)?.into(),
+            blue_spymaster: spymaster(Team::Blue)?.into(),
+        })
     }
 
-    /// Determines if the board should be hidden from the player, for using with game_state.get_hidden_board()
-    pub fn should_hide_board(&self) -> bool {
-        if self.red_operative.is_player() || self.blue_operative.is_player() {
-            return true;
-        } else if self.red_spymaster.is_player() || self.blue_spymaster.is_player() {
-            return false;
+    /// Hands a seat over to a human who joined the game
+    pub fn set_player(&mut self, seat: &Seat) {
+        match seat {
+            Seat::RedOperative => self.red_operative = Arc::new(Player),
+            Seat::RedSpymaster => self.red_spymaster = Arc::new(Player),
+            Seat::BlueOperative => self.blue_operative = Arc::new(Player),
+            Seat::BlueSpymaster => self.blue_spymaster = Arc::new(Player),
         }
-
-        unreachable!("At least one player should be a player")
     }
 }
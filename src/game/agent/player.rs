@@ -1,18 +1,26 @@
 use crate::game::game_state::{Clue, GameState};
 
+use super::{AgentMove, OperativeAgent, SpymasterAgent};
+
+/// A seat played by a human, moves come in through the routes instead
 pub struct Player;
 
-impl Player {
-    pub async fn try_gen_guesses(&self, _game_state: &GameState) -> Option<Vec<String>> {
-        None
+impl OperativeAgent for Player {
+    fn is_player(&self) -> bool {
+        true
+    }
+
+    fn try_gen_guesses<'a>(&'a self, _game_state: &'a GameState) -> AgentMove<'a, Vec<String>> {
+        Box::pin(async { Ok(None) })
     }
+}
 
+impl SpymasterAgent for Player {
     fn is_player(&self) -> bool {
-        tracing::debug!("Is Player True");
         true
     }
 
-    pub async fn try_gen_clue(&self, _game_state: &GameState) -> Option<Clue> {
-        None
+    fn try_gen_clue<'a>(&'a self, _game_state: &'a GameState) -> AgentMove<'a, Clue> {
+        Box::pin(async { Ok(None) })
     }
 }
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::game::{game_error::GameError, game_state::Team};
+
+use super::{
+    chatgpt::ChatGpt,
+    embeddings::{EmbeddingOperative, EmbeddingSpymaster, Embeddings},
+    mock::{MockOperative, MockSpymaster},
+    OperativeAgent, SpymasterAgent,
+};
+
+const DEFAULT_AGENT: &str = "gpt-4o-two-step";
+
+type OperativeFactory = Box<dyn Fn(Team) -> Box<dyn OperativeAgent> + Send + Sync>;
+type SpymasterFactory = Box<dyn Fn(Team) -> Box<dyn SpymasterAgent> + Send + Sync>;
+
+/// Every agent a seat can be given, by the name games are created with
+pub struct AgentRegistry {
+    operatives: HashMap<String, OperativeFactory>,
+    spymasters: HashMap<String, SpymasterFactory>,
+    default_agent: String,
+}
+
+impl AgentRegistry {
+    /// Embedding agents are only available when word vectors were loaded
+    pub fn new(embeddings: Option<Arc<Embeddings>>) -> Self {
+        let mut registry = Self {
+            operatives: HashMap::new(),
+            spymasters: HashMap::new(),
+            default_agent: DEFAULT_AGENT.to_string(),
+        };
+
+        for (name, model) in [
+            ("gpt-4o-two-step", "gpt-4o"),
+            ("gpt-4o-mini-two-step", "gpt-4o-mini"),
+        ] {
+            registry.register_operative(name, move |team| Box::new(ChatGpt::new(team, model)));
+            registry.register_spymaster(name, move |team| Box::new(ChatGpt::new(team, model)));
+        }
+
+        registry.register_operative("mock", |team| {
+            Box::new(MockOperative::new(team, Vec::new()))
+        });
+        registry.register_spymaster("mock", |team| {
+            Box::new(MockSpymaster::new(team, Vec::new()))
+        });
+
+        if let Some(embeddings) = embeddings {
+            let operative_embeddings = embeddings.clone();
+            registry.register_operative("embeddings", move |team| {
+                Box::new(EmbeddingOperative::new(operative_embeddings.clone(), team))
+            });
+            registry.register_spymaster("embeddings", move |team| {
+                Box::new(EmbeddingSpymaster::new(embeddings.clone(), team))
+            });
+        }
+
+        registry
+    }
+
+    /// Agent given to seats a game does not choose an agent for
+    pub fn default_agent(&self) -> &str {
+        &self.default_agent
+    }
+
+    /// The default has to play both roles, so it must be registered as both
+    pub fn set_default_agent(&mut self, name: &str) -> Result<(), GameError> {
+        if !self.operatives.contains_key(name) || !self.spymasters.contains_key(name) {
+            return Err(GameError::UnknownAgent(name.to_string()));
+        }
+
+        self.default_agent = name.to_string();
+        Ok(())
+    }
+
+    pub fn register_operative(
+        &mut self,
+        name: &str,
+        factory: impl Fn(Team) -> Box<dyn OperativeAgent> + Send + Sync + 'static,
+    ) {
+        self.operatives.insert(name.to_string(), Box::new(factory));
+    }
+
+    pub fn register_spymaster(
+        &mut self,
+        name: &str,
+        factory: impl Fn(Team) -> Box<dyn SpymasterAgent> + Send + Sync + 'static,
+    ) {
+        self.spymasters.insert(name.to_string(), Box::new(factory));
+    }
+
+    pub fn operative(&self, name: &str, team: Team) -> Result<Box<dyn OperativeAgent>, GameError> {
+        let factory = self
+            .operatives
+            .get(name)
+            .ok_or_else(|| GameError::UnknownAgent(name.to_string()))?;
+        Ok(factory(team))
+    }
+
+    pub fn spymaster(&self, name: &str, team: Team) -> Result<Box<dyn SpymasterAgent>, GameError> {
+        let factory = self
+            .spymasters
+            .get(name)
+            .ok_or_else(|| GameError::UnknownAgent(name.to_string()))?;
+        Ok(factory(team))
+    }
+}
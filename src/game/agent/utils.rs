@@ -1,10 +1,18 @@
 use itertools::Itertools;
 
-use crate::game::game_state::Card;
+use crate::game::{game_event::GameEvent, game_state::Card};
 
-pub fn board_string(board: &[Card]) -> String {
+pub fn board_string(board: &[Card], columns: usize) -> String {
     board
-        .chunks(5)
+        .chunks(columns)
         .map(|chunk| chunk.iter().map(|card| card.to_string()).join(","))
         .join("\n")
 }
+
+pub fn history_string(events: &[GameEvent]) -> String {
+    if events.is_empty() {
+        return "Nothing has happened yet.".to_string();
+    }
+
+    events.iter().map(|event| event.to_string()).join("\n")
+}
@@ -0,0 +1,192 @@
+use std::fmt::Display;
+
+use super::game_state::Card;
+
+/// Why a clue is not allowed on the current board
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClueRejection {
+    Empty,
+    MultipleWords,
+    NonAlphabetic,
+    BoardWord(String),
+    ContainsBoardWord(String),
+    ContainedInBoardWord(String),
+    SharedStem(String),
+}
+
+impl Display for ClueRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClueRejection::Empty => write!(f, "The clue is empty"),
+            ClueRejection::MultipleWords => write!(f, "The clue must be a single word"),
+            ClueRejection::NonAlphabetic => write!(f, "The clue can only contain letters"),
+            ClueRejection::BoardWord(word) => write!(f, "The clue is the board word {}", word),
+            ClueRejection::ContainsBoardWord(word) => {
+                write!(f, "The clue contains the board word {}", word)
+            }
+            ClueRejection::ContainedInBoardWord(word) => {
+                write!(f, "The clue is part of the board word {}", word)
+            }
+            ClueRejection::SharedStem(word) => {
+                write!(f, "The clue shares a stem with the board word {}", word)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClueRejection {}
+
+/// Checks a clue against the unrevealed words on the board, ignoring case
+pub fn validate_clue(clue: &str, board: &[Card]) -> Result<(), ClueRejection> {
+    let clue = clue.trim();
+
+    if clue.is_empty() {
+        return Err(ClueRejection::Empty);
+    }
+
+    if clue.split_whitespace().count() > 1 {
+        return Err(ClueRejection::MultipleWords);
+    }
+
+    if !clue.chars().all(char::is_alphabetic) {
+        return Err(ClueRejection::NonAlphabetic);
+    }
+
+    let clue = clue.to_lowercase();
+    let clue_stem = stem(&clue);
+
+    for card in board.iter().filter(|card| !card.guessed()) {
+        let word = card.word().trim().to_lowercase();
+
+        if clue == word {
+            return Err(ClueRejection::BoardWord(card.word().to_string()));
+        }
+
+        if clue.contains(&word) {
+            return Err(ClueRejection::ContainsBoardWord(card.word().to_string()));
+        }
+
+        if word.contains(&clue) {
+            return Err(ClueRejection::ContainedInBoardWord(card.word().to_string()));
+        }
+
+        if clue_stem == stem(&word) {
+            return Err(ClueRejection::SharedStem(card.word().to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Strips common English inflections so "Beaches" and "beach" compare equal
+fn stem(word: &str) -> String {
+    if let Some(base) = word.strip_suffix("ies") {
+        return format!("{base}y");
+    }
+
+    for suffix in ["sses", "shes", "ches", "xes", "zes"] {
+        if word.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+
+    for suffix in ["ing", "ed"] {
+        if let Some(base) = word.strip_suffix(suffix) {
+            if base.len() > 2 {
+                return base.to_string();
+            }
+        }
+    }
+
+    match word.strip_suffix('s') {
+        Some(base) if !base.ends_with('s') => base.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::game::game_state::Identity;
+
+    /// A clue other tests can give on any board dealt from the word bank
+    pub(crate) fn legal_clue(board: &[Card]) -> &'static str {
+        ["quixotic", "zyzzyva", "xylophone"]
+            .into_iter()
+            .find(|word| validate_clue(word, board).is_ok())
+            .unwrap()
+    }
+
+    fn cards(words: &[&str]) -> Vec<Card> {
+        words
+            .iter()
+            .map(|word| Card::new(word.to_string(), Identity::Bystander))
+            .collect()
+    }
+
+    #[test]
+    fn rejects_malformed_clues() {
+        let board = cards(&["Beach"]);
+        assert_eq!(validate_clue("  ", &board), Err(ClueRejection::Empty));
+        assert_eq!(
+            validate_clue("sand castle", &board),
+            Err(ClueRejection::MultipleWords)
+        );
+        assert_eq!(
+            validate_clue("sand-castle", &board),
+            Err(ClueRejection::NonAlphabetic)
+        );
+        assert_eq!(validate_clue("ocean", &board), Ok(()));
+    }
+
+    #[test]
+    fn rejects_board_words_in_any_case() {
+        let board = cards(&["Beach"]);
+        for clue in ["Beach", "beach", "BEACH", " bEaCh "] {
+            assert_eq!(
+                validate_clue(clue, &board),
+                Err(ClueRejection::BoardWord("Beach".to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_containment_in_both_directions() {
+        let board = cards(&["Beach", "Sunflower"]);
+        assert_eq!(
+            validate_clue("Beaches", &board),
+            Err(ClueRejection::ContainsBoardWord("Beach".to_string()))
+        );
+        assert_eq!(
+            validate_clue("BEACHCOMBER", &board),
+            Err(ClueRejection::ContainsBoardWord("Beach".to_string()))
+        );
+        assert_eq!(
+            validate_clue("flower", &board),
+            Err(ClueRejection::ContainedInBoardWord("Sunflower".to_string()))
+        );
+        assert_eq!(
+            validate_clue("SUN", &board),
+            Err(ClueRejection::ContainedInBoardWord("Sunflower".to_string()))
+        );
+
+        let board = cards(&["Beaches"]);
+        assert_eq!(
+            validate_clue("beach", &board),
+            Err(ClueRejection::ContainedInBoardWord("Beaches".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_shared_stems() {
+        let board = cards(&["Berry", "Hoping"]);
+        assert_eq!(
+            validate_clue("Berries", &board),
+            Err(ClueRejection::SharedStem("Berry".to_string()))
+        );
+        assert_eq!(
+            validate_clue("hoped", &board),
+            Err(ClueRejection::SharedStem("Hoping".to_string()))
+        );
+    }
+}
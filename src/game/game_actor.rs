@@ -0,0 +1,814 @@
+use std::{collections::VecDeque, future::Future, time::Duration};
+
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::AbortHandle,
+    time::sleep,
+};
+
+use super::{
+    game_controller::{AgentPlay, GameController, GameData, Role},
+    game_error::GameError,
+    game_event::now_millis,
+    game_state::{ClueCount, GameState, GuessTarget},
+    game_view::View,
+};
+
+const COMMAND_BUFFER: usize = 32;
+/// Pause after each AI guess so they can be followed one at a time
+const GUESS_DELAY: Duration = Duration::from_millis(1000);
+/// Times the agent is asked for a move in a row before stepping stops
+const MAX_AGENT_ATTEMPTS: usize = 3;
+
+type Reply<T> = oneshot::Sender<Result<T, GameError>>;
+
+/// Everything a game's task can be asked to do, answered on the reply channel
+enum Command {
+    Join {
+        role: Role,
+        reply: Reply<String>,
+    },
+    Guess {
+        token: Option<String>,
+        guess: GuessTarget,
+        reply: Reply<()>,
+    },
+    Clue {
+        token: Option<String>,
+        word: String,
+        count: ClueCount,
+        reply: Reply<()>,
+    },
+    Pass {
+        token: Option<String>,
+        reply: Reply<()>,
+    },
+    Forfeit {
+        token: Option<String>,
+        reply: Reply<()>,
+    },
+    Start {
+        token: Option<String>,
+        reply: Reply<()>,
+    },
+    GameData {
+        token: Option<String>,
+        view: View,
+        reply: Reply<GameData>,
+    },
+    GameStateAt {
+        step: usize,
+        reply: Reply<GameState>,
+    },
+    Rewind {
+        step: usize,
+        reply: Reply<()>,
+    },
+    VerifyReplay {
+        reply: Reply<usize>,
+    },
+    Delete {
+        token: Option<String>,
+        reply: Reply<()>,
+    },
+    EnforceTimer,
+    /// The game as it is now, if it is due to be evicted
+    Expired {
+        expiry: Expiry,
+        reply: oneshot::Sender<Option<(Eviction, GameState)>>,
+    },
+    /// Stops the game if it is still due and has not moved on from `events` events
+    Evict {
+        expiry: Expiry,
+        events: usize,
+        reply: oneshot::Sender<bool>,
+    },
+    /// An agent finished thinking about the game as it was after `events` events
+    AgentMoved {
+        events: usize,
+        result: Result<Option<AgentPlay>, GameError>,
+    },
+    /// Time for the AI's next guess, unless the game moved on since `events` events
+    NextGuess {
+        events: usize,
+    },
+}
+
+impl Command {
+    /// Whether the command came from someone using the game, rather than the server itself
+    fn is_activity(&self) -> bool {
+        !matches!(
+            self,
+            Command::EnforceTimer
+                | Command::Expired { .. }
+                | Command::Evict { .. }
+                | Command::AgentMoved { .. }
+                | Command::NextGuess { .. }
+        )
+    }
+}
+
+/// Games that ended or were last used before these times, in milliseconds since the Unix epoch,
+/// are due to be evicted
+#[derive(Debug, Clone, Copy)]
+pub struct Expiry {
+    pub idle_before: u64,
+    pub ended_before: u64,
+}
+
+/// Why a game was stopped to free up its memory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Eviction {
+    Idle,
+    Finished,
+}
+
+/// A game running in its own task, requests to it never wait on any other game
+#[derive(Clone)]
+pub struct GameHandle {
+    sender: mpsc::Sender<Command>,
+}
+
+impl GameHandle {
+    pub fn spawn(controller: GameController) -> Self {
+        let (sender, receiver) = mpsc::channel(COMMAND_BUFFER);
+        let actor = GameActor {
+            controller,
+            receiver,
+            sender: sender.downgrade(),
+            guesses: VecDeque::new(),
+            driver: None,
+            waiters: Vec::new(),
+            failures: 0,
+            agent_error: None,
+            last_active: now_millis(),
+            stopped: false,
+        };
+        tokio::spawn(actor.run());
+
+        Self { sender }
+    }
+
+    /// The game is gone once its task has stopped taking commands
+    async fn request<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<T>) -> Command,
+    ) -> Result<T, GameError> {
+        let (reply, response) = oneshot::channel();
+        self.sender
+            .send(command(reply))
+            .await
+            .map_err(|_| GameError::GameNotFound)?;
+        response.await.map_err(|_| GameError::GameNotFound)
+    }
+
+    pub async fn join(&self, role: Role) -> Result<String, GameError> {
+        self.request(|reply| Command::Join { role, reply }).await?
+    }
+
+    pub async fn player_guess(
+        &self,
+        token: Option<&str>,
+        guess: GuessTarget,
+    ) -> Result<(), GameError> {
+        let token = token.map(str::to_string);
+        self.request(|reply| Command::Guess {
+            token,
+            guess,
+            reply,
+        })
+        .await?
+    }
+
+    pub async fn player_clue(
+        &self,
+        token: Option<&str>,
+        word: String,
+        count: ClueCount,
+    ) -> Result<(), GameError> {
+        let token = token.map(str::to_string);
+        self.request(|reply| Command::Clue {
+            token,
+            word,
+            count,
+            reply,
+        })
+        .await?
+    }
+
+    pub async fn player_pass(&self, token: Option<&str>) -> Result<(), GameError> {
+        let token = token.map(str::to_string);
+        self.request(|reply| Command::Pass { token, reply }).await?
+    }
+
+    pub async fn player_forfeit(&self, token: Option<&str>) -> Result<(), GameError> {
+        let token = token.map(str::to_string);
+        self.request(|reply| Command::Forfeit { token, reply })
+            .await?
+    }
+
+    /// Waits for the AI to hand the game to a player, an AI-only game is left playing instead
+    pub async fn start(&self, token: Option<&str>) -> Result<(), GameError> {
+        let token = token.map(str::to_string);
+        self.request(|reply| Command::Start { token, reply })
+            .await?
+    }
+
+    pub async fn game_data(&self, token: Option<&str>, view: View) -> Result<GameData, GameError> {
+        let token = token.map(str::to_string);
+        self.request(|reply| Command::GameData { token, view, reply })
+            .await?
+    }
+
+    pub async fn game_state_at(&self, step: usize) -> Result<GameState, GameError> {
+        self.request(|reply| Command::GameStateAt { step, reply })
+            .await?
+    }
+
+    pub async fn rewind(&self, step: usize) -> Result<(), GameError> {
+        self.request(|reply| Command::Rewind { step, reply })
+            .await?
+    }
+
+    pub async fn verify_replay(&self) -> Result<usize, GameError> {
+        self.request(|reply| Command::VerifyReplay { reply })
+            .await?
+    }
+
+    /// Stops the game and whatever the AI is doing in it, later requests find no game
+    pub async fn delete(&self, token: Option<&str>) -> Result<(), GameError> {
+        let token = token.map(str::to_string);
+        self.request(|reply| Command::Delete { token, reply })
+            .await?
+    }
+
+    /// The game's state to archive, if it is due to be evicted
+    pub async fn expired(
+        &self,
+        expiry: Expiry,
+    ) -> Result<Option<(Eviction, GameState)>, GameError> {
+        self.request(|reply| Command::Expired { expiry, reply })
+            .await
+    }
+
+    /// Stops the game unless it was used or moved on since it was archived with `events` events
+    pub async fn evict(&self, expiry: Expiry, events: usize) -> Result<bool, GameError> {
+        self.request(|reply| Command::Evict {
+            expiry,
+            events,
+            reply,
+        })
+        .await
+    }
+
+    /// Skipped when the game is backed up, the next check catches it instead
+    pub fn enforce_timer(&self) {
+        let _ = self.sender.try_send(Command::EnforceTimer);
+    }
+}
+
+/// The one task moving a game along for the AI, an agent thinking or the pause between guesses
+struct Driver {
+    /// Events the game had when the task started, it is stale once there are more
+    events: usize,
+    task: AbortHandle,
+}
+
+struct GameActor {
+    controller: GameController,
+    receiver: mpsc::Receiver<Command>,
+    /// Weak so the task ends once every handle to the game is dropped
+    sender: mpsc::WeakSender<Command>,
+    /// Guesses the AI still wants to make this turn
+    guesses: VecDeque<String>,
+    driver: Option<Driver>,
+    /// Starts waiting for the AI to hand the game to a player
+    waiters: Vec<Reply<()>>,
+    /// Moves the agent has failed to make in a row
+    failures: usize,
+    /// Why the agent's last move failed, cleared once it makes one
+    agent_error: Option<String>,
+    /// Last time someone used the game, in milliseconds since the Unix epoch
+    last_active: u64,
+    stopped: bool,
+}
+
+impl GameActor {
+    async fn run(mut self) {
+        while let Some(command) = self.receiver.recv().await {
+            if command.is_activity() {
+                self.last_active = now_millis();
+            }
+
+            self.handle(command);
+            if self.stopped {
+                break;
+            }
+        }
+
+        self.stop_driving();
+        tracing::debug!("Game task stopped");
+    }
+
+    fn handle(&mut self, command: Command) {
+        match command {
+            Command::Join { role, reply } => {
+                let _ = reply.send(self.controller.join(role));
+            }
+            Command::Guess {
+                token,
+                guess,
+                reply,
+            } => {
+                let result = self.controller.player_guess(token.as_deref(), guess);
+                self.moved(reply, result);
+            }
+            Command::Clue {
+                token,
+                word,
+                count,
+                reply,
+            } => {
+                let result = self.controller.player_clue(token.as_deref(), word, count);
+                self.moved(reply, result);
+            }
+            Command::Pass { token, reply } => {
+                let result = self.controller.player_pass(token.as_deref());
+                self.moved(reply, result);
+            }
+            Command::Forfeit { token, reply } => {
+                let result = self.controller.player_forfeit(token.as_deref());
+                self.moved(reply, result);
+            }
+            // Starting again only waits on the driver already moving the game
+            Command::Start { token, reply } => {
+                if let Err(err) = self.controller.check_player(token.as_deref()) {
+                    let _ = reply.send(Err(err));
+                    return;
+                }
+
+                self.controller.start();
+                match self.controller.is_spectated() {
+                    true => {
+                        let _ = reply.send(Ok(()));
+                    }
+                    false => self.waiters.push(reply),
+                }
+                self.step();
+            }
+            Command::GameData { token, view, reply } => {
+                let stepping = self.is_stepping();
+                let agent_error = self.agent_error.clone();
+                let _ = reply.send(self.controller.game_data(
+                    token.as_deref(),
+                    view,
+                    stepping,
+                    agent_error,
+                ));
+            }
+            Command::GameStateAt { step, reply } => {
+                let _ = reply.send(self.controller.game_state_at(step));
+            }
+            Command::Rewind { step, reply } => {
+                let result = self.controller.rewind(step);
+                self.stop_driving();
+                self.moved(reply, result);
+            }
+            Command::VerifyReplay { reply } => {
+                let _ = reply.send(self.controller.verify_replay());
+            }
+            Command::Delete { token, reply } => {
+                let result = self.controller.check_player(token.as_deref());
+                self.stopped = result.is_ok();
+                let _ = reply.send(result);
+            }
+            Command::EnforceTimer => {
+                if self.controller.enforce_timer() {
+                    self.stop_driving();
+                    self.step();
+                }
+            }
+            Command::Expired { expiry, reply } => {
+                let game_state = self.controller.game_state();
+                let expired = self
+                    .eviction(expiry)
+                    .map(|eviction| (eviction, game_state.clone()));
+                let _ = reply.send(expired);
+            }
+            Command::Evict {
+                expiry,
+                events,
+                reply,
+            } => {
+                self.stopped = self.eviction(expiry).is_some() && events == self.events();
+                let _ = reply.send(self.stopped);
+            }
+            Command::AgentMoved { events, result } => {
+                if self.take_driver(events) {
+                    self.agent_moved(result);
+                }
+            }
+            Command::NextGuess { events } => {
+                if self.take_driver(events) {
+                    self.next_guess();
+                }
+            }
+        }
+    }
+
+    fn eviction(&self, expiry: Expiry) -> Option<Eviction> {
+        match self.controller.game_state().ended_at() {
+            Some(ended_at) if ended_at < expiry.ended_before => Some(Eviction::Finished),
+            _ if self.last_active < expiry.idle_before => Some(Eviction::Idle),
+            _ => None,
+        }
+    }
+
+    fn events(&self) -> usize {
+        self.controller.game_state().events().len()
+    }
+
+    /// Answers a move, then lets the AI take any turns that follow it
+    fn moved(&mut self, reply: Reply<()>, result: Result<(), GameError>) {
+        let moved = result.is_ok();
+        let _ = reply.send(result);
+        if moved {
+            self.step();
+        }
+    }
+
+    fn is_stepping(&self) -> bool {
+        self.driver
+            .as_ref()
+            .is_some_and(|driver| driver.events == self.events())
+    }
+
+    /// Runs the game's next AI task, its command comes back once it is done
+    fn drive(&mut self, task: impl Future<Output = Command> + Send + 'static) {
+        let Some(sender) = self.sender.upgrade() else {
+            return;
+        };
+
+        let task = tokio::spawn(async move {
+            let _ = sender.send(task.await).await;
+        });
+        self.driver = Some(Driver {
+            events: self.events(),
+            task: task.abort_handle(),
+        });
+    }
+
+    /// Clears the driver a finished task belonged to, false if the game has moved on without it
+    fn take_driver(&mut self, events: usize) -> bool {
+        match &self.driver {
+            Some(driver) if driver.events == events && events == self.events() => {
+                self.driver = None;
+                true
+            }
+            _ => {
+                tracing::info!("Discarding stale AI move");
+                false
+            }
+        }
+    }
+
+    /// Cancels what the AI was doing, it no longer fits the game
+    fn stop_driving(&mut self) {
+        if let Some(driver) = self.driver.take() {
+            driver.task.abort();
+        }
+        self.guesses.clear();
+    }
+
+    /// Asks the agent whose turn it is for a move, in its own task so the game keeps answering
+    fn step(&mut self) {
+        // Nobody moves for the AI before the game is started
+        if !self.controller.is_started() {
+            return;
+        }
+
+        if self.is_stepping() {
+            tracing::debug!(
+                "Already stepping: {:?}",
+                self.controller.game_state().phase()
+            );
+            return;
+        }
+
+        // The turn moved on without the AI, say a player forfeited while it was thinking
+        self.stop_driving();
+
+        let Some(turn) = self.controller.agent_turn() else {
+            self.finish(Ok(()));
+            return;
+        };
+
+        // A copy of what the seat may see, so the timer can still pass the turn while the agent is thinking
+        let game_state = self.controller.agent_view();
+        let events = game_state.events().len();
+        tracing::info!("Stepping game: {:?}", game_state.phase());
+
+        self.drive(async move {
+            let result = turn.play(game_state).await;
+            Command::AgentMoved { events, result }
+        });
+    }
+
+    fn agent_moved(&mut self, result: Result<Option<AgentPlay>, GameError>) {
+        match result {
+            Ok(Some(AgentPlay::Clue(clue))) => match self.controller.ai_clue(clue) {
+                Ok(()) => {
+                    self.agent_played();
+                    self.step();
+                }
+                Err(err) => self.illegal_move(err),
+            },
+            Ok(Some(AgentPlay::Guesses(guesses))) => {
+                tracing::debug!("AI Guesses: {:?}", guesses);
+                self.guesses = guesses.into();
+                self.next_guess();
+            }
+            Ok(None) => self.finish(Ok(())),
+            Err(err) => self.agent_failed(err),
+        }
+    }
+
+    /// Makes the AI's next guess, passing once it has none left or one fails
+    fn next_guess(&mut self) {
+        let failed = match self.guesses.pop_front() {
+            Some(guess) => match self.controller.ai_guess(guess) {
+                Ok(()) => {
+                    self.agent_played();
+                    let events = self.events();
+                    self.drive(async move {
+                        sleep(GUESS_DELAY).await;
+                        Command::NextGuess { events }
+                    });
+                    return;
+                }
+                Err(err) => Some(err),
+            },
+            None => None,
+        };
+
+        self.guesses.clear();
+        match self.controller.ai_pass() {
+            Ok(()) => self.step(),
+            // Passing fails when no guess was made, the failed guess says why
+            Err(err) => self.illegal_move(failed.unwrap_or(err)),
+        }
+    }
+
+    fn illegal_move(&mut self, err: GameError) {
+        self.agent_failed(GameError::AgentFailed(err.to_string()));
+    }
+
+    /// Asks the agent again, until it has failed too often in a row and stepping stops until retried
+    fn agent_failed(&mut self, err: GameError) {
+        tracing::warn!("Could not step the game: {}", err);
+        self.agent_error = Some(err.to_string());
+        self.failures += 1;
+        if self.failures < MAX_AGENT_ATTEMPTS {
+            self.step();
+            return;
+        }
+
+        self.failures = 0;
+        self.finish(Err(err));
+    }
+
+    fn agent_played(&mut self) {
+        self.failures = 0;
+        self.agent_error = None;
+    }
+
+    /// Stepping has stopped, so starts waiting on it get their answer
+    fn finish(&mut self, result: Result<(), GameError>) {
+        for waiter in self.waiters.drain(..) {
+            let _ = waiter.send(result.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::game::{
+        agent::{registry::AgentRegistry, SeatAgents},
+        clue_validator::tests::legal_clue,
+        game_event::EventKind,
+        game_state::{
+            tests::{config, words},
+            EndReason, Phase, Seat, Team,
+        },
+        game_view::SpectatorView,
+        word_bank::WordBank,
+    };
+
+    fn spawn_game() -> GameHandle {
+        let game_state = GameState::new(config(json!({})), &WordBank::new(), 7);
+        let controller = GameController::new(
+            Role::RedTeam,
+            game_state,
+            false,
+            None,
+            &AgentRegistry::new(None),
+            &SeatAgents::default(),
+        )
+        .unwrap();
+        GameHandle::spawn(controller)
+    }
+
+    #[tokio::test]
+    async fn games_are_evicted_once_idle() {
+        let game = spawn_game();
+        let idle = |idle_before| Expiry {
+            idle_before,
+            ended_before: 0,
+        };
+        assert!(game
+            .expired(idle(now_millis() - 60_000))
+            .await
+            .unwrap()
+            .is_none());
+
+        let expiry = idle(now_millis() + 1_000);
+        let (eviction, game_state) = game.expired(expiry).await.unwrap().unwrap();
+        assert_eq!(eviction, Eviction::Idle);
+        assert!(game.verify_replay().await.is_ok());
+
+        let events = game_state.events().len();
+        assert!(game.evict(expiry, events).await.unwrap());
+        assert!(game.verify_replay().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn finished_games_are_evicted_after_their_own_ttl() {
+        let game = spawn_game();
+        game.player_forfeit(None).await.unwrap();
+
+        // Ended just now, so the finished TTL has not run out yet
+        let minute_ago = now_millis() - 60_000;
+        let expiry = |ended_before| Expiry {
+            idle_before: minute_ago,
+            ended_before,
+        };
+        assert!(game.expired(expiry(minute_ago)).await.unwrap().is_none());
+
+        let (eviction, game_state) = game
+            .expired(expiry(now_millis() + 1_000))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(eviction, Eviction::Finished);
+        assert_eq!(game_state.winner(), Some(&Team::Blue));
+    }
+
+    #[tokio::test]
+    async fn evictions_are_called_off_when_the_game_moves_on() {
+        let game = spawn_game();
+        let expiry = Expiry {
+            idle_before: now_millis() + 1_000,
+            ended_before: 0,
+        };
+        let (_, game_state) = game.expired(expiry).await.unwrap().unwrap();
+
+        // A clue arrives while the game is being archived
+        let word = legal_clue(game_state.board()).to_string();
+        game.player_clue(None, word, ClueCount::Number(1))
+            .await
+            .unwrap();
+
+        let events = game_state.events().len();
+        assert!(!game.evict(expiry, events).await.unwrap());
+        assert!(game.verify_replay().await.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn agents_stop_after_failing_too_often() {
+        let game_state = GameState::new(config(json!({})), &WordBank::new(), 7);
+        let clue = legal_clue(game_state.board());
+        let choices: SeatAgents = serde_json::from_value(json!({
+            "redSpymaster": { "clues": [{ "word": clue, "count": 1 }] },
+            "redOperative": { "guesses": [["notacard"]] },
+        }))
+        .unwrap();
+        let controller = GameController::new(
+            Role::BlueTeam,
+            game_state,
+            false,
+            None,
+            &AgentRegistry::new(None),
+            &choices,
+        )
+        .unwrap();
+        let game = GameHandle::spawn(controller);
+
+        let err = game.start(None).await.unwrap_err();
+        assert!(err.to_string().contains("notacard"));
+
+        let GameData::Playing {
+            game_state,
+            stepping,
+            agent_error,
+            ..
+        } = game.game_data(None, View::Spymaster).await.unwrap();
+        assert!(!stepping);
+        assert_eq!(agent_error, Some(err.to_string()));
+        assert!(matches!(game_state.phase(), Phase::Guess { .. }));
+        assert_eq!(game_state.events().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scripted_game_plays_to_the_end() {
+        let game_state = GameState::new(config(json!({})), &WordBank::new(), 42);
+        let first = game_state.starting_team().clone();
+        let second = first.other();
+        let first_agents = words(&game_state, first.clone());
+        let second_agents = words(&game_state, second.clone());
+        let clue = legal_clue(game_state.board());
+
+        // The starting team finds one agent, the other team one, then the starting team the rest
+        let script = |agents: &[String], rest: bool| {
+            let guesses = match rest {
+                true => vec![agents[..1].to_vec(), agents[1..].to_vec()],
+                false => vec![agents[..1].to_vec()],
+            };
+            let clues: Vec<_> = guesses
+                .iter()
+                .map(|guesses| json!({ "word": clue, "count": guesses.len() }))
+                .collect();
+            (json!({ "clues": clues }), json!({ "guesses": guesses }))
+        };
+        let seat = |team: &Team| match team {
+            Team::Red => ("redSpymaster", "redOperative"),
+            Team::Blue => ("blueSpymaster", "blueOperative"),
+        };
+
+        let mut choices = serde_json::Map::new();
+        for (team, agents, rest) in [
+            (&first, &first_agents, true),
+            (&second, &second_agents, false),
+        ] {
+            let (spymaster, operative) = script(agents, rest);
+            choices.insert(seat(team).0.to_string(), spymaster);
+            choices.insert(seat(team).1.to_string(), operative);
+        }
+        let choices: SeatAgents = serde_json::from_value(choices.into()).unwrap();
+
+        let controller = GameController::new(
+            Role::Spectator,
+            game_state,
+            false,
+            Some(SpectatorView::Spymaster),
+            &AgentRegistry::new(None),
+            &choices,
+        )
+        .unwrap();
+        let game = GameHandle::spawn(controller);
+        game.start(None).await.unwrap();
+
+        let game_state = loop {
+            sleep(Duration::from_millis(100)).await;
+            let GameData::Playing { game_state, .. } =
+                game.game_data(None, View::Spymaster).await.unwrap();
+            if let Phase::End { .. } = game_state.phase() {
+                break game_state;
+            }
+        };
+
+        assert_eq!(game_state.winner(), Some(&first));
+
+        let kinds: Vec<&EventKind> = game_state
+            .events()
+            .iter()
+            .map(|event| event.kind())
+            .collect();
+        let count =
+            |matches: fn(&EventKind) -> bool| kinds.iter().filter(|kind| matches(kind)).count();
+        assert_eq!(count(|kind| matches!(kind, EventKind::ClueGiven { .. })), 3);
+        assert_eq!(
+            count(|kind| matches!(kind, EventKind::GuessMade { .. })),
+            first_agents.len() + 1
+        );
+        assert_eq!(
+            kinds.last(),
+            Some(&&EventKind::GameEnded {
+                winner: Some(first.clone()),
+                reason: EndReason::AllAgentsFound,
+            })
+        );
+
+        let seats: Vec<&Seat> = game_state
+            .events()
+            .iter()
+            .map(|event| event.seat())
+            .collect();
+        assert_eq!(seats[0], &Seat::spymaster(&first));
+        assert_eq!(seats[1], &Seat::operative(&first));
+        assert!(seats.contains(&&Seat::spymaster(&second)));
+        assert_eq!(game.verify_replay().await.unwrap(), game_state.steps());
+    }
+}
@@ -0,0 +1,244 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    game_state::{Identity, Team},
+    house_rules::HouseRules,
+};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum GameMode {
+    /// Red against Blue
+    #[default]
+    Classic,
+    /// Both sides work together against a shared pool of turns
+    Duet {
+        #[serde(default = "GameMode::default_duet_turns")]
+        turns: u8,
+    },
+}
+
+impl GameMode {
+    fn default_duet_turns() -> u8 {
+        9
+    }
+}
+
+/// Time limits enforced by the server, running out passes the turn
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum TurnTimer {
+    /// Seconds for each clue and for each round of guessing
+    PerPhase { clue: u32, guess: u32 },
+    /// Seconds each team has for the whole game, running out loses it
+    ChessClock { team: u32 },
+}
+
+impl TurnTimer {
+    const MIN_SECONDS: u32 = 10;
+    const MAX_SECONDS: u32 = 2 * 60 * 60;
+
+    fn validate(&self) -> Result<()> {
+        let seconds = match self {
+            TurnTimer::PerPhase { clue, guess } => [*clue, *guess],
+            TurnTimer::ChessClock { team } => [*team, *team],
+        };
+
+        if seconds
+            .iter()
+            .any(|seconds| !(Self::MIN_SECONDS..=Self::MAX_SECONDS).contains(seconds))
+        {
+            bail!(
+                "Timers must be between {} and {} seconds",
+                Self::MIN_SECONDS,
+                Self::MAX_SECONDS
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Board dimensions and key card distribution for a single game
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GameConfig {
+    rows: u8,
+    columns: u8,
+    /// Agents belonging to the team that gives the first clue
+    #[serde(rename = "startingAgents")]
+    starting_agents: u8,
+    #[serde(rename = "otherAgents")]
+    other_agents: u8,
+    bystanders: u8,
+    assassins: u8,
+    /// Picked at random when not set
+    #[serde(rename = "startingTeam", default)]
+    starting_team: Option<Team>,
+    #[serde(default)]
+    mode: GameMode,
+    #[serde(rename = "houseRules", default)]
+    house_rules: HouseRules,
+    #[serde(default)]
+    timer: Option<TurnTimer>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            rows: 5,
+            columns: 5,
+            starting_agents: 9,
+            other_agents: 8,
+            bystanders: 7,
+            assassins: 1,
+            starting_team: None,
+            mode: GameMode::Classic,
+            house_rules: HouseRules::default(),
+            timer: None,
+        }
+    }
+}
+
+impl GameConfig {
+    const MIN_DIMENSION: u8 = 3;
+    const MAX_DIMENSION: u8 = 8;
+
+    pub fn validate(&self) -> Result<()> {
+        let dimensions = Self::MIN_DIMENSION..=Self::MAX_DIMENSION;
+        if !dimensions.contains(&self.rows) || !dimensions.contains(&self.columns) {
+            bail!(
+                "Board must be between {0}x{0} and {1}x{1}",
+                Self::MIN_DIMENSION,
+                Self::MAX_DIMENSION
+            );
+        }
+
+        if let Some(timer) = &self.timer {
+            timer.validate()?;
+        }
+
+        // Duet always deals the official two-sided key card
+        if let GameMode::Duet { turns } = self.mode {
+            if self.rows != 5 || self.columns != 5 {
+                bail!("Duet is played on a 5x5 board");
+            }
+
+            if turns == 0 {
+                bail!("Duet needs at least one turn");
+            }
+
+            if self.house_rules != HouseRules::default() {
+                bail!("House rules only apply to classic games");
+            }
+
+            if let Some(TurnTimer::ChessClock { .. }) = self.timer {
+                bail!("Duet has no teams to give a chess clock to");
+            }
+
+            return Ok(());
+        }
+
+        if self.other_agents == 0 {
+            bail!("Each team needs at least one agent");
+        }
+
+        if self.starting_agents < self.other_agents {
+            bail!("The starting team can't have fewer agents than the other team");
+        }
+
+        if self.assassins == 0 {
+            bail!("There must be at least one assassin");
+        }
+
+        self.house_rules.validate()?;
+
+        if self.house_rules.double_agent() && self.starting_agents == self.other_agents {
+            bail!("The double agent needs the starting team to have an extra agent");
+        }
+
+        let cards = self.starting_agents as usize
+            + self.other_agents as usize
+            + self.bystanders as usize
+            + self.assassins as usize;
+
+        if cards != self.size() {
+            bail!(
+                "Key card has {} cards but a {}x{} board needs {}",
+                cards,
+                self.rows,
+                self.columns,
+                self.size()
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    pub fn columns(&self) -> u8 {
+        self.columns
+    }
+
+    pub fn size(&self) -> usize {
+        self.rows as usize * self.columns as usize
+    }
+
+    pub fn starting_team(&self) -> Option<&Team> {
+        self.starting_team.as_ref()
+    }
+
+    pub fn mode(&self) -> &GameMode {
+        &self.mode
+    }
+
+    pub fn house_rules(&self) -> &HouseRules {
+        &self.house_rules
+    }
+
+    pub fn timer(&self) -> Option<&TurnTimer> {
+        self.timer.as_ref()
+    }
+
+    /// Unshuffled key card, the starting team gets the extra agents or shares one as a double agent
+    pub fn identities(&self, starting_team: &Team) -> Vec<Identity> {
+        let double_agents = self.house_rules.double_agent() as u8;
+        let starting_agents = self.starting_agents - double_agents;
+        let (red_agents, blue_agents) = match starting_team {
+            Team::Red => (starting_agents, self.other_agents),
+            Team::Blue => (self.other_agents, starting_agents),
+        };
+
+        [
+            (Identity::Red, red_agents),
+            (Identity::Blue, blue_agents),
+            (Identity::DoubleAgent, double_agents),
+            (Identity::Bystander, self.bystanders),
+            (Identity::Assassin, self.assassins),
+        ]
+        .into_iter()
+        .flat_map(|(identity, count)| std::iter::repeat_n(identity, count as usize))
+        .collect()
+    }
+
+    /// Unshuffled Duet key card as (Red side, Blue side), each side has 9 agents and 3 assassins
+    pub fn duet_identities(&self) -> Vec<(Identity, Identity)> {
+        [
+            ((Identity::Red, Identity::Blue), 3),
+            ((Identity::Red, Identity::Bystander), 5),
+            ((Identity::Bystander, Identity::Blue), 5),
+            ((Identity::Red, Identity::Assassin), 1),
+            ((Identity::Assassin, Identity::Blue), 1),
+            ((Identity::Assassin, Identity::Assassin), 1),
+            ((Identity::Assassin, Identity::Bystander), 1),
+            ((Identity::Bystander, Identity::Assassin), 1),
+            ((Identity::Bystander, Identity::Bystander), 7),
+        ]
+        .into_iter()
+        .flat_map(|(sides, count)| std::iter::repeat_n(sides, count))
+        .collect()
+    }
+}
@@ -1,202 +1,488 @@
-use std::time::Duration;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
-use tokio::{
-    sync::{RwLock},
-    time::sleep,
-};
+use uuid::Uuid;
 
 use crate::routes::game::GetGameResponse;
 
 use super::{
-    agent::{Agents, Operative, Spymaster},
-    game_state::{Clue, GameState, Phase, Team},
+    agent::{registry::AgentRegistry, Agents, OperativeAgent, SeatAgents, SpymasterAgent},
+    game_config::GameMode,
+    game_error::GameError,
+    game_event::now_millis,
+    game_state::{Clue, ClueCount, GameState, GuessTarget, Phase, Seat, Team},
+    game_view::{SpectatorView, View, Viewer},
 };
 
+/// The seats the human plays, the AI takes every other seat
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Role {
     RedOperative,
     RedSpymaster,
+    BlueOperative,
+    BlueSpymaster,
+    /// Gives the clues for both teams
+    Spymasters,
+    RedTeam,
+    BlueTeam,
+    /// Watches the AI play every seat
+    Spectator,
+}
+
+impl Role {
+    /// Duet players always play the Red side, whichever role they pick
+    pub fn seats(&self, mode: &GameMode) -> Vec<Seat> {
+        if let (GameMode::Duet { .. }, false) = (mode, *self == Role::Spectator) {
+            return vec![Seat::RedSpymaster, Seat::BlueOperative];
+        }
+
+        match self {
+            Role::RedOperative => vec![Seat::RedOperative],
+            Role::RedSpymaster => vec![Seat::RedSpymaster],
+            Role::BlueOperative => vec![Seat::BlueOperative],
+            Role::BlueSpymaster => vec![Seat::BlueSpymaster],
+            Role::Spymasters => vec![Seat::RedSpymaster, Seat::BlueSpymaster],
+            Role::RedTeam => vec![Seat::RedSpymaster, Seat::RedOperative],
+            Role::BlueTeam => vec![Seat::BlueSpymaster, Seat::BlueOperative],
+            Role::Spectator => vec![],
+        }
+    }
 }
 
 pub type GameData = GetGameResponse;
 
+/// The agent whose move the game is waiting on
+pub enum AgentTurn {
+    Clue(Arc<dyn SpymasterAgent>),
+    Guess(Arc<dyn OperativeAgent>),
+}
+
+/// A move an agent came up with, made once it is known to still be that agent's turn
+#[derive(Debug)]
+pub enum AgentPlay {
+    Clue(Clue),
+    Guesses(Vec<String>),
+}
+
+impl AgentTurn {
+    /// Owns everything it needs, so it can run without holding up the game
+    pub async fn play(self, game_state: GameState) -> Result<Option<AgentPlay>, GameError> {
+        match self {
+            AgentTurn::Clue(spymaster) => Ok(spymaster
+                .try_gen_clue(&game_state)
+                .await?
+                .map(AgentPlay::Clue)),
+            AgentTurn::Guess(operative) => Ok(operative
+                .try_gen_guesses(&game_state)
+                .await?
+                .map(AgentPlay::Guesses)),
+        }
+    }
+}
+
+/// The rules around a single game, owned by the task that runs it
 pub struct GameController {
-    // TODO: make RwLock<GameState> the self method type
-    game_state: RwLock<GameState>,
+    game_state: GameState,
+    /// Swapped out as players claim seats before the game starts
     agents: Agents,
     role: Role,
+    /// Seats claimed by each player's token, only multiplayer games use tokens
+    players: HashMap<String, Vec<Seat>>,
+    multiplayer: bool,
+    spectator_view: SpectatorView,
+    started: bool,
 }
 
 impl GameController {
-    pub fn new(role: Role, words: Vec<String>) -> Self {
-        let game_state = GameState::new(words);
-        let agents = Agents::new(role.clone());
-        GameController {
-            game_state: RwLock::new(game_state),
+    /// Multiplayer games start with the AI in every seat until players join
+    ///
+    /// Spectators can pick their own view unless the host chose one, or other players could be watching
+    pub fn new(
+        role: Role,
+        game_state: GameState,
+        multiplayer: bool,
+        spectator_view: Option<SpectatorView>,
+        registry: &AgentRegistry,
+        choices: &SeatAgents,
+    ) -> Result<Self, GameError> {
+        let seats = match multiplayer {
+            true => vec![],
+            false => role.seats(game_state.config().mode()),
+        };
+        let agents = Agents::new(registry, choices, &seats)?;
+        Ok(GameController {
+            game_state,
             agents,
             role,
-        }
+            players: HashMap::new(),
+            multiplayer,
+            spectator_view: spectator_view.unwrap_or(match multiplayer {
+                true => SpectatorView::Operative,
+                false => SpectatorView::Choice,
+            }),
+            started: false,
+        })
     }
 
-    pub async fn player_guess(&self, guess: String) -> Option<()> {
-        tracing::info!("Player Clue: Init");
+    pub fn game_state(&self) -> &GameState {
+        &self.game_state
+    }
 
-        if !self.is_player_turn().await {
-            tracing::info!("Player Clue: Not player turn");
-            return None;
+    /// Claims a role's seats for a new player, returning the token they act with
+    pub fn join(&mut self, role: Role) -> Result<String, GameError> {
+        if !self.multiplayer {
+            return Err(GameError::NotMultiplayer);
+        }
+
+        if self.started {
+            return Err(GameError::AlreadyStarted);
+        }
+
+        let seats = role.seats(self.game_state.config().mode());
+        if seats.is_empty() {
+            return Err(GameError::NotAPlayer);
+        }
+
+        if self
+            .players
+            .values()
+            .flatten()
+            .any(|seat| seats.contains(seat))
+        {
+            return Err(GameError::SeatTaken);
         }
 
-        let mut game_state = self.game_state.write().await;
-        if let Ok(()) = game_state.make_guess(guess) {
-            return Some(());
+        for seat in &seats {
+            self.agents.set_player(seat);
         }
 
-        tracing::info!("Not correct phase");
-        None
+        tracing::info!("Seats claimed: {:?}", seats);
+        let token = Uuid::new_v4().simple().to_string();
+        self.players.insert(token.clone(), seats);
+
+        Ok(token)
     }
 
-    pub async fn player_clue(&self, word: String, count: u8) -> Option<()> {
-        tracing::debug!("Player Clue: Init");
-        if !self.is_player_turn().await {
-            tracing::info!("Player Clue: Not player turn");
-            return None;
+    /// Seats a request may act for, single player games need no token
+    fn seats_for(&self, token: Option<&str>) -> Result<Vec<Seat>, GameError> {
+        if !self.multiplayer {
+            return Ok(self.role.seats(self.game_state.config().mode()));
         }
 
-        let clue = Clue::new(word, count);
-        let mut game_state = self.game_state.write().await;
-        if let Ok(()) = game_state.provide_clue(clue) {
-            return Some(());
+        token
+            .and_then(|token| self.players.get(token))
+            .cloned()
+            .ok_or(GameError::NotAuthorized)
+    }
+
+    pub fn player_guess(
+        &mut self,
+        token: Option<&str>,
+        guess: GuessTarget,
+    ) -> Result<(), GameError> {
+        tracing::info!("Player Clue: Init");
+        self.check_player_turn(token)?;
+        self.game_state.make_guess(guess)
+    }
+
+    pub fn player_clue(
+        &mut self,
+        token: Option<&str>,
+        word: String,
+        count: ClueCount,
+    ) -> Result<(), GameError> {
+        tracing::debug!("Player Clue: Init");
+        self.check_player_turn(token)?;
+        self.game_state.provide_clue(Clue::new(word, count))
+    }
+
+    pub fn player_pass(&mut self, token: Option<&str>) -> Result<(), GameError> {
+        tracing::debug!("Player Pass: Init");
+        self.check_player_turn(token)?;
+        self.game_state.end_turn()
+    }
+
+    pub fn player_forfeit(&mut self, token: Option<&str>) -> Result<(), GameError> {
+        tracing::debug!("Player Forfeit: Init");
+
+        let seats = self.seats_for(token)?;
+        let team = match self.game_state.phase() {
+            Phase::Clue { team } | Phase::Guess { team, .. } => Some(team.clone()),
+            Phase::End { .. } => None,
+        };
+
+        // Someone playing for both teams gives up for the team whose turn it is
+        let seat = seats
+            .iter()
+            .find(|seat| Some(seat.team()) == team)
+            .or(seats.first())
+            .ok_or(GameError::NotAPlayer)?;
+        self.game_state.forfeit(seat.clone())
+    }
+
+    /// Anyone can manage a single player game, only players with a seat can manage a multiplayer one
+    pub fn check_player(&self, token: Option<&str>) -> Result<(), GameError> {
+        self.seats_for(token).map(|_| ())
+    }
+
+    fn check_player_turn(&self, token: Option<&str>) -> Result<(), GameError> {
+        let seats = self.seats_for(token)?;
+        let Some(seat) = self.game_state.current_seat() else {
+            return Err(GameError::GameOver);
+        };
+
+        if seats.contains(&seat) && self.is_player_turn() {
+            return Ok(());
         }
 
-        tracing::info!("Not correct phase");
-        None
+        tracing::info!("Not player turn");
+        Err(GameError::NotYourTurn)
+    }
+
+    pub fn game_state_at(&self, step: usize) -> Result<GameState, GameError> {
+        self.game_state.at(step)
+    }
+
+    pub fn rewind(&mut self, step: usize) -> Result<(), GameError> {
+        self.game_state = self.game_state.at(step)?;
+        self.game_state.resume_timer();
+        tracing::info!("Rewound to step {}: {:?}", step, self.game_state.phase());
+        Ok(())
     }
 
-    pub async fn step_until_input(&self) {
-        if self.is_player_turn().await {
-            tracing::info!(
-                "Stepping aborted early cause player turn {:?}",
-                self.game_state.read().await.phase()
-            );
-            return;
+    /// Passes the turn if it has run out of time, returning whether it did
+    pub fn enforce_timer(&mut self) -> bool {
+        if !self.started {
+            return false;
         }
 
-        tracing::info!(
-            "Initiating Stepping: {:?}",
-            self.game_state.read().await.phase()
-        );
-        while self.step_game().await.is_some() {
-            tracing::info!("Stepping game: {:?}", self.game_state.read().await.phase());
+        let timed_out = self.game_state.enforce_timer(now_millis());
+        if timed_out {
+            tracing::info!("Timed out: {:?}", self.game_state.phase());
         }
 
-        tracing::info!("Player Turn: {:?}", self.game_state.read().await.phase());
+        timed_out
     }
 
-    async fn step_game(&self) -> Option<()> {
-        let phase = self.game_state.read().await.phase().clone();
-        match phase {
-            Phase::Clue { team: Team::Red } => {
-                self.try_apply_clue(&self.agents.red_spymaster).await
-            }
+    pub fn verify_replay(&self) -> Result<usize, GameError> {
+        self.game_state.verify_replay()?;
+        Ok(self.game_state.steps())
+    }
+
+    /// Seats can no longer be claimed once the AI starts moving, and turn clocks start running
+    pub fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.game_state.start_timer();
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    /// The agent to ask for the next move, None while waiting on a player or once the game is over
+    pub fn agent_turn(&self) -> Option<AgentTurn> {
+        if self.is_player_turn() {
+            tracing::info!("Player Turn: {:?}", self.game_state.phase());
+            return None;
+        }
+
+        let agents = &self.agents;
+        match self.game_state.phase() {
+            Phase::Clue { team: Team::Red } => Some(AgentTurn::Clue(agents.red_spymaster.clone())),
             Phase::Clue { team: Team::Blue } => {
-                self.try_apply_clue(&self.agents.blue_spymaster).await
+                Some(AgentTurn::Clue(agents.blue_spymaster.clone()))
             }
             Phase::Guess {
                 team: Team::Blue, ..
-            } => self.try_apply_guess(&self.agents.blue_operative).await,
+            } => Some(AgentTurn::Guess(agents.blue_operative.clone())),
             Phase::Guess {
                 team: Team::Red, ..
-            } => self.try_apply_guess(&self.agents.red_operative).await,
-            Phase::End => None,
+            } => Some(AgentTurn::Guess(agents.red_operative.clone())),
+            Phase::End { .. } => None,
         }
     }
 
-    async fn is_player_turn(&self) -> bool {
-        {
-            tracing::debug!("Is Player Turn: {:?}", self.game_state.read().await.phase());
-        }
+    /// The game as the seat whose turn it is may see it, so the key never reaches an operative agent
+    pub fn agent_view(&self) -> GameState {
+        let seats = self.game_state.current_seat().into_iter().collect();
+        Viewer::Seats(seats).project(&self.game_state)
+    }
 
-        match self.game_state.read().await.phase() {
-            Phase::Clue { team: Team::Red } => {
-                tracing::debug!("Red Spymaster Check");
-                self.agents.red_spymaster.is_player()
-            }
-            Phase::Clue { team: Team::Blue } => {
-                tracing::debug!("Blue Spymaster Check");
-                self.agents.blue_spymaster.is_player()
-            }
+    fn is_player_turn(&self) -> bool {
+        tracing::debug!("Is Player Turn: {:?}", self.game_state.phase());
+
+        let agents = &self.agents;
+        match self.game_state.phase() {
+            Phase::Clue { team: Team::Red } => agents.red_spymaster.is_player(),
+            Phase::Clue { team: Team::Blue } => agents.blue_spymaster.is_player(),
             Phase::Guess {
                 team: Team::Blue, ..
-            } => {
-                tracing::debug!("Blue Operative Check");
-                self.agents.blue_operative.is_player()
-            }
+            } => agents.blue_operative.is_player(),
             Phase::Guess {
                 team: Team::Red, ..
-            } => {
-                tracing::debug!("Blue Operative Check");
-                self.agents.red_operative.is_player()
-            }
-            Phase::End => false,
+            } => agents.red_operative.is_player(),
+            Phase::End { .. } => false,
         }
     }
 
-    async fn try_apply_clue(&self, spymaster: &Spymaster) -> Option<()> {
-        let clue = {
-            let game_state = self.game_state.read().await;
-            spymaster.try_gen_clue(&game_state).await
-        };
+    pub fn ai_clue(&mut self, clue: Clue) -> Result<(), GameError> {
+        tracing::debug!("AI Clue: {:?}", clue);
+        self.game_state.provide_clue(clue)
+    }
 
-        if let Some(clue) = clue {
-            tracing::debug!("AI Clue: {:?}", clue);
-            let _ = self.game_state.write().await.provide_clue(clue);
-            return Some(());
+    pub fn ai_guess(&mut self, guess: String) -> Result<(), GameError> {
+        tracing::debug!("AI Guess: {:?}", guess);
+        self.game_state.make_guess(GuessTarget::Word(guess))
+    }
+
+    /// The operative stops once it has no more guesses it wants to make
+    ///
+    /// Fails if it stops before making a single guess, say when its first guess was not on the board.
+    pub fn ai_pass(&mut self) -> Result<(), GameError> {
+        if self.game_state.clue().is_none() {
+            return Ok(());
         }
 
-        None
+        tracing::debug!("AI Pass");
+        self.game_state.end_turn()
     }
 
-    async fn try_apply_guess(&self, operative: &Operative) -> Option<()> {
-        let guesses = {
-            let game_state = self.game_state.read().await;
-            operative.try_gen_guesses(&game_state).await
-        };
+    pub fn is_spectated(&self) -> bool {
+        self.role == Role::Spectator
+    }
 
-        if let Some(guesses) = guesses {
-            tracing::debug!("AI Guesses: {:?}", guesses);
-            for guess in guesses {
-                tracing::debug!("AI Guess: {:?}", guess);
-                let guess_result = {
-                    let mut game_state = self.game_state.write().await;
-                    game_state.make_guess(guess)
-                };
-
-                if guess_result.is_ok() {
-                    sleep(Duration::from_millis(1000)).await;
-                } else {
-                    break;
-                }
-            }
+    /// A token that is sent must belong to a seat, so nobody can pass as a spectator by mistake
+    pub fn game_data(
+        &self,
+        token: Option<&str>,
+        view: View,
+        stepping: bool,
+        agent_error: Option<String>,
+    ) -> Result<GameData, GameError> {
+        let game_state = &self.game_state;
+        let role = self.role.clone();
+        let starting_team = game_state.starting_team().clone();
+        let winner = game_state.winner().cloned();
+        let scores = game_state.scores();
+        let timer = match self.started {
+            true => game_state.timer_status(now_millis()),
+            false => None,
+        };
 
-            return Some(());
-        }
+        // Only a seat's own token shows its view, anyone without a token is a spectator
+        let seats = match (token, self.multiplayer) {
+            (None, true) => Vec::new(),
+            _ => self.seats_for(token)?,
+        };
+        let viewer = match (seats.is_empty(), self.spectator_view) {
+            (false, _) => Viewer::Seats(seats.clone()),
+            (true, SpectatorView::Operative) => Viewer::Spectator(View::Operative),
+            (true, SpectatorView::Spymaster) => Viewer::Spectator(View::Spymaster),
+            (true, SpectatorView::Choice) => Viewer::Spectator(view),
+        };
+        let view = viewer.view(game_state);
+        let game_state = viewer.project(game_state);
 
-        None
+        Ok(GameData::Playing {
+            game_state,
+            role,
+            starting_team,
+            winner,
+            scores,
+            timer,
+            view,
+            seats,
+            stepping,
+            agent_error,
+        })
     }
+}
 
-    pub fn agents(&self) -> &Agents {
-        &self.agents
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::game::{
+        agent::SeatAgents,
+        clue_validator::tests::legal_clue,
+        game_state::{tests::config, Identity},
+        word_bank::WordBank,
+    };
+
+    #[test]
+    fn players_only_act_for_the_seats_they_claimed() {
+        let game_state = GameState::new(config(json!({})), &WordBank::new(), 7);
+        let mut game = GameController::new(
+            Role::Spectator,
+            game_state,
+            true,
+            None,
+            &AgentRegistry::new(None),
+            &SeatAgents::default(),
+        )
+        .unwrap();
+        let red = game.join(Role::RedSpymaster).unwrap();
+        let blue = game.join(Role::BlueTeam).unwrap();
+        assert!(matches!(
+            game.join(Role::BlueOperative),
+            Err(GameError::SeatTaken)
+        ));
+        game.start();
+
+        let word = legal_clue(game.game_state().board()).to_string();
+        let clue = |game: &mut GameController, token| {
+            game.player_clue(token, word.clone(), ClueCount::Number(1))
+        };
+        assert!(matches!(
+            clue(&mut game, None),
+            Err(GameError::NotAuthorized)
+        ));
+        assert!(matches!(
+            clue(&mut game, Some("not-a-token")),
+            Err(GameError::NotAuthorized)
+        ));
+        assert!(matches!(
+            clue(&mut game, Some(&blue)),
+            Err(GameError::NotYourTurn)
+        ));
+        clue(&mut game, Some(&red)).unwrap();
+
+        assert!(matches!(
+            game.join(Role::RedOperative),
+            Err(GameError::AlreadyStarted)
+        ));
     }
 
-    pub async fn game_data(&self) -> GameData {
-        let mut game_state = self.game_state.read().await.clone();
-        let role = self.role.clone();
+    #[test]
+    fn agents_only_see_what_their_seat_may() {
+        let game_state = GameState::new(config(json!({})), &WordBank::new(), 7);
+        let mut game = GameController::new(
+            Role::Spectator,
+            game_state,
+            false,
+            None,
+            &AgentRegistry::new(None),
+            &SeatAgents::default(),
+        )
+        .unwrap();
+        let hidden = |game_state: &GameState| {
+            game_state
+                .board()
+                .iter()
+                .filter(|card| *card.identity() == Identity::Hidden)
+                .count()
+        };
 
-        if self.agents().should_hide_board() {
-            game_state = game_state.to_hidden_game_state();
-        }
+        let spymaster_view = game.agent_view();
+        assert_eq!(hidden(&spymaster_view), 0);
 
-        GameData::Playing { game_state, role }
+        let word = legal_clue(game.game_state().board()).to_string();
+        game.ai_clue(Clue::new(word, ClueCount::Number(1))).unwrap();
+        let operative_view = game.agent_view();
+        assert_eq!(hidden(&operative_view), 25);
+        assert_eq!(operative_view.seed(), None);
     }
 }
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use async_openai::error::OpenAIError;
+
+use super::clue_validator::ClueRejection;
+
+/// Everything that can go wrong while setting up or playing a game
+#[derive(Clone, Debug)]
+pub enum GameError {
+    GameNotFound,
+    NotAuthorized,
+    NotAPlayer,
+    NotMultiplayer,
+    SeatTaken,
+    AlreadyStarted,
+    NotYourTurn,
+    WrongPhase,
+    GameOver,
+    NoGuessesMade,
+    CardAlreadyGuessed,
+    CardNotFound,
+    ClueCountTooHigh,
+    IllegalClue(ClueRejection),
+    InvalidConfig(String),
+    UnknownAgent(String),
+    StepOutOfRange { step: usize, last: usize },
+    ReplayMismatch(&'static str),
+    AgentRateLimited,
+    AgentFailed(String),
+}
+
+impl GameError {
+    /// Machine readable name for clients to match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            GameError::GameNotFound => "gameNotFound",
+            GameError::NotAuthorized => "notAuthorized",
+            GameError::NotAPlayer => "notAPlayer",
+            GameError::NotMultiplayer => "notMultiplayer",
+            GameError::SeatTaken => "seatTaken",
+            GameError::AlreadyStarted => "alreadyStarted",
+            GameError::NotYourTurn => "notYourTurn",
+            GameError::WrongPhase => "wrongPhase",
+            GameError::GameOver => "gameOver",
+            GameError::NoGuessesMade => "noGuessesMade",
+            GameError::CardAlreadyGuessed => "cardAlreadyGuessed",
+            GameError::CardNotFound => "cardNotFound",
+            GameError::ClueCountTooHigh => "clueCountTooHigh",
+            GameError::IllegalClue(_) => "illegalClue",
+            GameError::InvalidConfig(_) => "invalidConfig",
+            GameError::UnknownAgent(_) => "unknownAgent",
+            GameError::StepOutOfRange { .. } => "stepOutOfRange",
+            GameError::ReplayMismatch(_) => "replayMismatch",
+            GameError::AgentRateLimited => "agentRateLimited",
+            GameError::AgentFailed(_) => "agentFailed",
+        }
+    }
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::GameNotFound => write!(f, "Could not find the game"),
+            GameError::NotAuthorized => write!(f, "Not authorized"),
+            GameError::NotAPlayer => write!(f, "Spectators cannot play"),
+            GameError::NotMultiplayer => write!(f, "Only multiplayer games can be joined"),
+            GameError::SeatTaken => write!(f, "That seat has already been claimed"),
+            GameError::AlreadyStarted => write!(f, "The game has already started"),
+            GameError::NotYourTurn => write!(f, "It is not your turn"),
+            GameError::WrongPhase => write!(f, "That move is not allowed in this phase"),
+            GameError::GameOver => write!(f, "The game is already over"),
+            GameError::NoGuessesMade => write!(f, "At least one guess must be made"),
+            GameError::CardAlreadyGuessed => write!(f, "Card has already been guessed"),
+            GameError::CardNotFound => write!(f, "Guess is not found on the board"),
+            GameError::ClueCountTooHigh => {
+                write!(f, "The clue count is more than the agents left")
+            }
+            GameError::IllegalClue(rejection) => write!(f, "{}", rejection),
+            GameError::InvalidConfig(message) => write!(f, "{}", message),
+            GameError::UnknownAgent(name) => write!(f, "There is no agent called {}", name),
+            GameError::StepOutOfRange { step, last } => {
+                write!(f, "Step {} is past the last step {}", step, last)
+            }
+            GameError::ReplayMismatch(part) => write!(f, "Replayed {} does not match", part),
+            GameError::AgentRateLimited => write!(f, "The AI is being rate limited"),
+            GameError::AgentFailed(message) => write!(f, "The AI failed to move: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<ClueRejection> for GameError {
+    fn from(rejection: ClueRejection) -> Self {
+        GameError::IllegalClue(rejection)
+    }
+}
+
+impl From<OpenAIError> for GameError {
+    fn from(err: OpenAIError) -> Self {
+        match &err {
+            OpenAIError::ApiError(api_error)
+                if api_error.code.as_deref() == Some("rate_limit_exceeded") =>
+            {
+                GameError::AgentRateLimited
+            }
+            _ => GameError::AgentFailed(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::{
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use super::game_state::{ClueCount, EndReason, Identity, Seat, Team};
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub enum TurnEndReason {
+    Passed,
+    OutOfGuesses,
+    WrongCard,
+    Skipped,
+    Timeout,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum EventKind {
+    ClueGiven {
+        word: String,
+        count: ClueCount,
+    },
+    GuessMade {
+        word: String,
+        index: usize,
+        identity: Identity,
+    },
+    TurnEnded {
+        team: Team,
+        reason: TurnEndReason,
+    },
+    GameEnded {
+        winner: Option<Team>,
+        reason: EndReason,
+    },
+}
+
+impl EventKind {
+    /// Whether a seat chose to do this, rather than it following from an earlier action
+    pub fn is_action(&self) -> bool {
+        match self {
+            EventKind::ClueGiven { .. } | EventKind::GuessMade { .. } => true,
+            EventKind::TurnEnded { reason, .. } => {
+                matches!(reason, TurnEndReason::Passed | TurnEndReason::Timeout)
+            }
+            EventKind::GameEnded { reason, .. } => {
+                matches!(reason, EndReason::Forfeit | EndReason::Timeout)
+            }
+        }
+    }
+}
+
+/// Milliseconds since the Unix epoch
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+/// Something that happened during the game, in the order it happened
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct GameEvent {
+    /// Milliseconds since the Unix epoch
+    timestamp: u64,
+    seat: Seat,
+    #[serde(flatten)]
+    kind: EventKind,
+}
+
+impl GameEvent {
+    pub fn new(seat: Seat, kind: EventKind) -> Self {
+        GameEvent {
+            timestamp: now_millis(),
+            seat,
+            kind,
+        }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.timestamp = timestamp;
+    }
+
+    pub fn seat(&self) -> &Seat {
+        &self.seat
+    }
+
+    pub fn kind(&self) -> &EventKind {
+        &self.kind
+    }
+}
+
+impl Display for GameEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            EventKind::ClueGiven { word, count } => {
+                write!(f, "{} gave the clue {}, {}", self.seat, word, count)
+            }
+            EventKind::GuessMade { word, identity, .. } => {
+                write!(f, "{} guessed {} ({})", self.seat, word, identity)
+            }
+            EventKind::TurnEnded { team, reason } => {
+                let reason = match reason {
+                    TurnEndReason::Passed => "the operative passed",
+                    TurnEndReason::OutOfGuesses => "no guesses were left",
+                    TurnEndReason::WrongCard => "a wrong card was revealed",
+                    TurnEndReason::Skipped => "it was lost to a bystander penalty",
+                    TurnEndReason::Timeout => "time ran out",
+                };
+                write!(f, "{} turn ended because {}", team, reason)
+            }
+            EventKind::GameEnded {
+                winner: Some(winner),
+                reason,
+            } => write!(f, "{} won the game ({:?})", winner, reason),
+            EventKind::GameEnded {
+                winner: None,
+                reason,
+            } => write!(f, "The game ended ({:?})", reason),
+        }
+    }
+}
@@ -1,8 +1,20 @@
 use std::fmt::Display;
 
-use anyhow::Result;
-use rand::seq::SliceRandom;
-use serde::Serialize;
+use rand::{
+    rngs::StdRng,
+    seq::{IndexedRandom, SliceRandom},
+    SeedableRng,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    clue_validator::validate_clue,
+    game_config::{GameConfig, GameMode, TurnTimer},
+    game_error::GameError,
+    game_event::{now_millis, EventKind, GameEvent, TurnEndReason},
+    house_rules::AssassinRule,
+    word_bank::WordBank,
+};
 
 #[derive(Clone, Debug, Serialize, PartialEq)]
 pub enum Identity {
@@ -10,6 +22,7 @@ pub enum Identity {
     Blue,
     Bystander,
     Assassin,
+    DoubleAgent,
     Hidden,
 }
 
@@ -20,6 +33,7 @@ impl Display for Identity {
             Identity::Blue => "Blue",
             Identity::Bystander => "Bystander",
             Identity::Assassin => "Assassin",
+            Identity::DoubleAgent => "Double Agent",
             Identity::Hidden => "Hidden",
         };
 
@@ -27,11 +41,21 @@ impl Display for Identity {
     }
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, PartialEq)]
 pub struct Card {
     word: String,
     guessed: bool,
+    /// In Duet this is the Red side of the key card
     identity: Identity,
+    /// The Blue side of a Duet key card
+    #[serde(rename = "duetIdentity", skip_serializing_if = "Option::is_none")]
+    duet_identity: Option<Identity>,
+    /// Duet sides whose key showed a bystander, the card stays in play for the other side
+    #[serde(rename = "bystanderFor", skip_serializing_if = "Vec::is_empty")]
+    bystander_for: Vec<Team>,
+    /// Whose key the card was guessed with
+    #[serde(skip)]
+    guessed_by: Option<Team>,
 }
 
 impl Card {
@@ -40,6 +64,16 @@ impl Card {
             word,
             guessed: false,
             identity,
+            duet_identity: None,
+            bystander_for: Vec::new(),
+            guessed_by: None,
+        }
+    }
+
+    pub fn new_duet(word: String, red_side: Identity, blue_side: Identity) -> Self {
+        Card {
+            duet_identity: Some(blue_side),
+            ..Card::new(word, red_side)
         }
     }
 
@@ -54,6 +88,36 @@ impl Card {
     pub fn guessed(&self) -> bool {
         self.guessed
     }
+
+    pub fn bystander_for(&self) -> &[Team] {
+        &self.bystander_for
+    }
+
+    /// The identity on the given team's key, both teams share one key outside of Duet
+    pub fn key(&self, team: &Team) -> &Identity {
+        match (team, &self.duet_identity) {
+            (Team::Blue, Some(duet_identity)) => duet_identity,
+            _ => &self.identity,
+        }
+    }
+
+    /// What a guessed card was revealed as on the key it was guessed with
+    fn revealed_identity(&self) -> &Identity {
+        match &self.guessed_by {
+            Some(team) => self.key(team),
+            None => &self.identity,
+        }
+    }
+
+    /// The card as it was dealt, before any guesses
+    fn dealt(&self) -> Self {
+        Card {
+            guessed: false,
+            bystander_for: Vec::new(),
+            guessed_by: None,
+            ..self.clone()
+        }
+    }
 }
 
 impl Display for Card {
@@ -73,44 +137,154 @@ impl Display for Card {
     }
 }
 
-#[derive(Clone, Debug, Serialize)]
+/// A number lets the operatives make one bonus guess, zero and unlimited let them guess freely
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(try_from = "ClueCountRepr", into = "ClueCountRepr")]
+pub enum ClueCount {
+    Number(u8),
+    Zero,
+    Unlimited,
+}
+
+impl From<u8> for ClueCount {
+    fn from(count: u8) -> Self {
+        match count {
+            0 => ClueCount::Zero,
+            count => ClueCount::Number(count),
+        }
+    }
+}
+
+impl Display for ClueCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClueCount::Number(count) => write!(f, "{}", count),
+            ClueCount::Zero => write!(f, "0"),
+            ClueCount::Unlimited => write!(f, "unlimited"),
+        }
+    }
+}
+
+/// Counts are sent as a number, or as "unlimited"
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+enum ClueCountRepr {
+    Number(u8),
+    Word(String),
+}
+
+impl TryFrom<ClueCountRepr> for ClueCount {
+    type Error = String;
+
+    fn try_from(repr: ClueCountRepr) -> std::result::Result<Self, Self::Error> {
+        match repr {
+            ClueCountRepr::Number(count) => Ok(count.into()),
+            ClueCountRepr::Word(word) if word.eq_ignore_ascii_case("unlimited") => {
+                Ok(ClueCount::Unlimited)
+            }
+            ClueCountRepr::Word(word) => Err(format!("Invalid clue count: {word}")),
+        }
+    }
+}
+
+impl From<ClueCount> for ClueCountRepr {
+    fn from(count: ClueCount) -> Self {
+        match count {
+            ClueCount::Number(count) => ClueCountRepr::Number(count),
+            ClueCount::Zero => ClueCountRepr::Number(0),
+            ClueCount::Unlimited => ClueCountRepr::Word("unlimited".to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
 pub struct Clue {
     word: String,
-    count: u8,
-    remaining: u8,
+    count: ClueCount,
+    /// Guesses made so far against this clue
+    guesses: u8,
+    /// Guesses left before the turn ends, `None` when unlimited
+    remaining: Option<u8>,
 }
 
 impl Clue {
-    pub fn new(word: String, count: u8) -> Self {
+    pub fn new(word: String, count: ClueCount) -> Self {
+        let remaining = match count {
+            ClueCount::Number(count) => Some(count.saturating_add(1)),
+            ClueCount::Zero | ClueCount::Unlimited => None,
+        };
+
         Clue {
-            word,
+            word: word.trim().to_string(),
             count,
-            remaining: count,
+            guesses: 0,
+            remaining,
         }
     }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    pub fn count(&self) -> &ClueCount {
+        &self.count
+    }
+
+    pub fn remaining(&self) -> Option<u8> {
+        self.remaining
+    }
 }
 
-#[derive(Clone, Debug, Serialize, PartialEq)]
+impl Display for Clue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.remaining {
+            Some(remaining) => write!(
+                f,
+                "{}, {} ({} guesses left)",
+                self.word, self.count, remaining
+            ),
+            None => write!(f, "{}, {} (unlimited guesses)", self.word, self.count),
+        }
+    }
+}
+
+/// A card named by its word, its index on the board or its row and column
+#[derive(Clone, Debug)]
+pub enum GuessTarget {
+    Word(String),
+    Index(usize),
+    Position { row: usize, column: usize },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub enum Team {
     Red,
     Blue,
 }
 
+impl Identity {
+    pub fn team(&self) -> Option<Team> {
+        match self {
+            Identity::Red => Some(Team::Red),
+            Identity::Blue => Some(Team::Blue),
+            _ => None,
+        }
+    }
+}
+
+/// The double agent works for both teams
 impl PartialEq<Team> for Identity {
     fn eq(&self, other: &Team) -> bool {
         matches!(
             (self, other),
-            (Identity::Red, Team::Red) | (Identity::Blue, Team::Blue)
+            (Identity::Red, Team::Red) | (Identity::Blue, Team::Blue) | (Identity::DoubleAgent, _)
         )
     }
 }
 
 impl PartialEq<Identity> for Team {
     fn eq(&self, other: &Identity) -> bool {
-        matches!(
-            (self, other),
-            (Team::Red, Identity::Red) | (Team::Blue, Identity::Blue)
-        )
+        other == self
     }
 }
 
@@ -134,139 +308,375 @@ impl Team {
     }
 }
 
+/// One of the four seats at the table
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub enum Seat {
+    RedSpymaster,
+    RedOperative,
+    BlueSpymaster,
+    BlueOperative,
+}
+
+impl Seat {
+    pub fn spymaster(team: &Team) -> Self {
+        match team {
+            Team::Red => Seat::RedSpymaster,
+            Team::Blue => Seat::BlueSpymaster,
+        }
+    }
+
+    pub fn operative(team: &Team) -> Self {
+        match team {
+            Team::Red => Seat::RedOperative,
+            Team::Blue => Seat::BlueOperative,
+        }
+    }
+
+    pub fn is_spymaster(&self) -> bool {
+        matches!(self, Seat::RedSpymaster | Seat::BlueSpymaster)
+    }
+
+    pub fn team(&self) -> Team {
+        match self {
+            Seat::RedSpymaster | Seat::RedOperative => Team::Red,
+            Seat::BlueSpymaster | Seat::BlueOperative => Team::Blue,
+        }
+    }
+}
+
+impl Display for Seat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seat = match self {
+            Seat::RedSpymaster => "Red Spymaster",
+            Seat::RedOperative => "Red Operative",
+            Seat::BlueSpymaster => "Blue Spymaster",
+            Seat::BlueOperative => "Blue Operative",
+        };
+
+        write!(f, "{}", seat)
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
+pub struct Scores {
+    red: i32,
+    blue: i32,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub enum EndReason {
+    AllAgentsFound,
+    AssassinRevealed,
+    Forfeit,
+    OutOfTurns,
+    Timeout,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum Phase {
-    Clue { team: Team },
-    Guess { team: Team, clue: Clue },
-    End,
+    Clue {
+        team: Team,
+    },
+    Guess {
+        team: Team,
+        clue: Clue,
+    },
+    /// Duet has no winning team, `reason` tells whether both sides won or lost together
+    End {
+        winner: Option<Team>,
+        reason: EndReason,
+    },
 }
 /// Legal moves only
 #[derive(Clone, Debug, Serialize)]
 pub struct GameState {
     board: Vec<Card>,
     phase: Phase,
+    config: GameConfig,
+    #[serde(rename = "startingTeam")]
+    starting_team: Team,
+    events: Vec<GameEvent>,
+    /// Reproduces the board, hidden from operatives until the game is over
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    /// Shared turn tokens left in Duet
+    #[serde(rename = "turnsLeft", skip_serializing_if = "Option::is_none")]
+    turns_left: Option<u8>,
+    /// Team that loses its next turn for revealing a bystander
+    #[serde(rename = "skipTurn", skip_serializing_if = "Option::is_none")]
+    skip_turn: Option<Team>,
+    /// Milliseconds since the Unix epoch
+    #[serde(rename = "createdAt")]
+    created_at: u64,
+    /// When play started, turn clocks do not run before then
+    #[serde(rename = "startedAt", skip_serializing_if = "Option::is_none")]
+    started_at: Option<u64>,
+    /// When the timer last started running, time before this is never charged
+    #[serde(skip)]
+    resumed_at: u64,
+}
+
+/// Milliseconds left on each team's chess clock
+#[derive(Clone, Debug, Serialize)]
+pub struct Clocks {
+    red: u64,
+    blue: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimerStatus {
+    /// Milliseconds since the Unix epoch when the current turn times out
+    deadline: u64,
+    /// Milliseconds until the deadline
+    remaining: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clocks: Option<Clocks>,
 }
 
 impl GameState {
-    const IDENTITIES_ARRAY: [Identity; 25] = [
-        Identity::Red,
-        Identity::Red,
-        Identity::Red,
-        Identity::Red,
-        Identity::Red,
-        Identity::Red,
-        Identity::Red,
-        Identity::Red,
-        Identity::Red,
-        Identity::Blue,
-        Identity::Blue,
-        Identity::Blue,
-        Identity::Blue,
-        Identity::Blue,
-        Identity::Blue,
-        Identity::Blue,
-        Identity::Blue,
-        Identity::Bystander,
-        Identity::Bystander,
-        Identity::Bystander,
-        Identity::Bystander,
-        Identity::Bystander,
-        Identity::Bystander,
-        Identity::Bystander,
-        Identity::Assassin,
-    ];
-
-    pub fn new(words: Vec<String>) -> Self {
-        let mut cards: Vec<Card> = words
-            .into_iter()
-            .zip(Self::IDENTITIES_ARRAY)
-            .map(|(word, identity)| Card::new(word, identity))
-            .collect();
+    pub fn new(config: GameConfig, word_bank: &WordBank, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let words = word_bank.get_word_set(config.size(), &mut rng);
+        let starting_team = match config.starting_team() {
+            Some(team) => team.clone(),
+            None => [Team::Red, Team::Blue].choose(&mut rng).unwrap().clone(),
+        };
+
+        let (mut cards, turns_left): (Vec<Card>, Option<u8>) = match config.mode() {
+            GameMode::Classic => (
+                words
+                    .into_iter()
+                    .zip(config.identities(&starting_team))
+                    .map(|(word, identity)| Card::new(word, identity))
+                    .collect(),
+                None,
+            ),
+            GameMode::Duet { turns } => (
+                words
+                    .into_iter()
+                    .zip(config.duet_identities())
+                    .map(|(word, (red_side, blue_side))| Card::new_duet(word, red_side, blue_side))
+                    .collect(),
+                Some(*turns),
+            ),
+        };
 
         tracing::debug!("{:?}", cards);
 
-        cards.shuffle(&mut rand::thread_rng());
-        let phase = Phase::Clue { team: Team::Red };
+        cards.shuffle(&mut rng);
+        let phase = Phase::Clue {
+            team: starting_team.clone(),
+        };
 
+        let created_at = now_millis();
         GameState {
             board: cards,
             phase,
+            config,
+            starting_team,
+            events: Vec::new(),
+            seed: Some(seed),
+            turns_left,
+            skip_turn: None,
+            created_at,
+            started_at: None,
+            resumed_at: created_at,
         }
     }
 
-    pub fn provide_clue(&mut self, clue: Clue) -> Result<()> {
-        if self.board.iter().any(|card| card.word == clue.word) {
-            tracing::debug!("The clue is a word on the board!");
-            return Err(anyhow::anyhow!("The clue is a word on the board!"));
+    pub fn is_duet(&self) -> bool {
+        matches!(self.config.mode(), GameMode::Duet { .. })
+    }
+
+    /// Whether the clue could be given right now, without giving it
+    pub fn check_clue(&self, clue: &Clue) -> Result<(), GameError> {
+        let team = match &self.phase {
+            Phase::Clue { team } => team,
+            Phase::End { .. } => return Err(GameError::GameOver),
+            _ => return Err(GameError::WrongPhase),
+        };
+
+        if let Err(rejection) = validate_clue(&clue.word, &self.board) {
+            tracing::debug!("Illegal clue: {}", rejection);
+            return Err(rejection.into());
         };
 
+        if let ClueCount::Number(count) = clue.count {
+            let unrevealed = self
+                .board
+                .iter()
+                .filter(|card| card.key(team) == team && !card.guessed)
+                .count();
+
+            if count as usize > unrevealed {
+                tracing::debug!("The clue count is more than the agents left!");
+                return Err(GameError::ClueCountTooHigh);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn provide_clue(&mut self, mut clue: Clue) -> Result<(), GameError> {
+        self.check_clue(&clue)?;
+
         tracing::debug!("GameState Provide Clue");
         // TODO: Make this an if let
         match &self.phase {
             Phase::Clue { team } => {
+                // Duet operatives keep guessing until they miss
+                if self.is_duet() {
+                    clue.remaining = None;
+                }
+
                 tracing::debug!("Succesfully gave clue: {:?}", &clue);
-                self.phase = Phase::Guess {
-                    team: team.clone(),
-                    clue,
-                };
+                let team = team.clone();
+                self.record(
+                    Seat::spymaster(&team),
+                    EventKind::ClueGiven {
+                        word: clue.word.clone(),
+                        count: clue.count.clone(),
+                    },
+                );
+                self.phase = Phase::Guess { team, clue };
                 tracing::debug!("New Phase: {:?}", &self.phase);
 
                 Ok(())
             }
-            _ => Err(anyhow::anyhow!("Wrong phase")),
+            Phase::End { .. } => Err(GameError::GameOver),
+            _ => Err(GameError::WrongPhase),
         }
     }
 
-    pub fn make_guess(&mut self, word: String) -> Result<()> {
+    pub fn make_guess(&mut self, target: GuessTarget) -> Result<(), GameError> {
         tracing::debug!("Making guess in game_state");
+        let index = self.card_index(&target);
 
         // TODO: Make this an if let
         match &mut self.phase {
             Phase::Guess { team, clue } => {
-                let card = self.board.iter_mut().find(|card| card.word == word);
-
-                if card.is_none() {
+                let Some(index) = index else {
                     tracing::debug!("Guess is not found on the board!");
-                    return Err(anyhow::anyhow!("Guess is not found on the board!"));
+                    return Err(GameError::CardNotFound);
                 };
 
-                let card = card.unwrap();
-                if card.guessed {
+                let card = &mut self.board[index];
+                if card.guessed || card.bystander_for.contains(team) {
                     tracing::debug!("Card has already been guessed!");
-                    return Err(anyhow::anyhow!("Card has already been guessed!"));
+                    return Err(GameError::CardAlreadyGuessed);
                 };
 
-                card.guessed = true;
-                clue.remaining -= 1;
+                let team = team.clone();
+                let identity = card.key(&team).clone();
+                let duet = card.duet_identity.is_some();
+
+                // A Duet bystander only covers the card for the side whose key showed it
+                if duet && identity == Identity::Bystander {
+                    card.bystander_for.push(team.clone());
+                } else {
+                    card.guessed = true;
+                    card.guessed_by = Some(team.clone());
+                }
+
+                clue.guesses += 1;
+                if let Some(remaining) = clue.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+
+                let out_of_guesses = clue.remaining == Some(0);
+                let seat = Seat::operative(&team);
+                self.record(
+                    seat.clone(),
+                    EventKind::GuessMade {
+                        word: self.board[index].word.clone(),
+                        index,
+                        identity: identity.clone(),
+                    },
+                );
 
-                if card.identity == Identity::Assassin {
+                let assassin_loses =
+                    duet || *self.config.house_rules().assassin() == AssassinRule::InstantLoss;
+                if identity == Identity::Assassin && assassin_loses {
                     tracing::debug!("Assassin has been guessed!");
-                    self.phase = Phase::End;
-                    tracing::debug!("New Phase: {:?}", &self.phase);
+                    let winner = (!duet).then(|| team.other());
+                    self.end_game(seat, winner, EndReason::AssassinRevealed);
                     return Ok(());
                 }
 
-                if clue.remaining == 0
-                    // TODO: Make better comparison between Identity and Team?
-                    || (card.identity == Identity::Blue && team == &Team::Red)
-                    || (card.identity == Identity::Red && team == &Team::Blue)
-                    || card.identity == Identity::Bystander
-                {
-                    self.phase = Phase::Clue { team: team.other() };
-                    tracing::debug!("New Phase: {:?}", &self.phase);
+                // Duet is won together once every agent on both sides is found
+                if duet && self.agents_found(&Team::Red) && self.agents_found(&Team::Blue) {
+                    self.end_game(seat, None, EndReason::AllAgentsFound);
+                    return Ok(());
                 }
 
-                if let Some(_team) = self.check_win_state() {
-                    self.phase = Phase::End;
-                    tracing::debug!("New Phase: {:?}", &self.phase);
+                // Revealing a team's last agent ends the game, even on the other team's turn
+                let owners = match &identity {
+                    _ if duet => vec![],
+                    Identity::DoubleAgent => vec![team.clone(), team.other()],
+                    identity => identity.team().into_iter().collect(),
+                };
+
+                if let Some(owner) = owners.into_iter().find(|owner| self.agents_found(owner)) {
+                    let winner = self.winner_on_points(owner);
+                    self.end_game(seat, Some(winner), EndReason::AllAgentsFound);
                     return Ok(());
                 }
 
-                tracing::debug!("Succesfully made guess: {word}");
+                if identity == Identity::Bystander && self.config.house_rules().bystander_penalty()
+                {
+                    self.skip_turn = Some(team.clone());
+                }
+
+                if identity != team {
+                    self.pass_turn(seat, team, TurnEndReason::WrongCard);
+                } else if out_of_guesses {
+                    self.pass_turn(seat, team, TurnEndReason::OutOfGuesses);
+                }
+
+                tracing::debug!("Succesfully made guess: {}", self.board[index].word);
+                Ok(())
+            }
+            Phase::End { .. } => Err(GameError::GameOver),
+            _ => Err(GameError::WrongPhase),
+        }
+    }
+
+    /// Guessed words are matched ignoring case and surrounding whitespace
+    fn card_index(&self, target: &GuessTarget) -> Option<usize> {
+        match target {
+            GuessTarget::Word(word) => {
+                let word = word.trim().to_lowercase();
+                self.board
+                    .iter()
+                    .position(|card| card.word.trim().to_lowercase() == word)
+            }
+            GuessTarget::Index(index) => (*index < self.board.len()).then_some(*index),
+            GuessTarget::Position { row, column } => {
+                let rows = self.config.rows() as usize;
+                let columns = self.config.columns() as usize;
+                (*row < rows && *column < columns).then_some(row * columns + column)
+            }
+        }
+    }
+
+    /// Operatives may stop guessing once they have made at least one guess
+    pub fn end_turn(&mut self) -> Result<(), GameError> {
+        tracing::debug!("Ending turn in game_state");
+
+        match &self.phase {
+            Phase::Guess { team, clue } => {
+                if clue.guesses == 0 {
+                    tracing::debug!("At least one guess must be made!");
+                    return Err(GameError::NoGuessesMade);
+                }
+
+                let team = team.clone();
+                self.pass_turn(Seat::operative(&team), team, TurnEndReason::Passed);
                 Ok(())
             }
-            _ => Err(anyhow::anyhow!("Wrong phase")),
+            Phase::End { .. } => Err(GameError::GameOver),
+            _ => Err(GameError::WrongPhase),
         }
     }
 
@@ -274,24 +684,334 @@ impl GameState {
         &self.board
     }
 
-    pub fn check_win_state(&self) -> Option<Team> {
-        let red_win = self
-            .board
+    /// Assassin penalties can cost the finishing team the game, finishing first only breaks a tie
+    fn winner_on_points(&self, finisher: Team) -> Team {
+        match self.config.house_rules().assassin() {
+            AssassinRule::Penalty { .. }
+                if self.score(&finisher.other()) > self.score(&finisher) =>
+            {
+                finisher.other()
+            }
+            _ => finisher,
+        }
+    }
+
+    fn agents_found(&self, team: &Team) -> bool {
+        self.board
             .iter()
-            .filter(|card| card.identity == Identity::Red)
-            .all(|card| card.guessed);
+            .filter(|card| card.key(team) == team)
+            .all(|card| card.guessed)
+    }
+
+    /// Either team can give up at any point before the game is over
+    pub fn forfeit(&mut self, seat: Seat) -> Result<(), GameError> {
+        tracing::debug!("Forfeiting in game_state");
+
+        if let Phase::End { .. } = self.phase {
+            return Err(GameError::GameOver);
+        }
+
+        let winner = (!self.is_duet()).then(|| seat.team().other());
+        self.end_game(seat, winner, EndReason::Forfeit);
+        Ok(())
+    }
+
+    fn pass_turn(&mut self, seat: Seat, team: Team, reason: TurnEndReason) {
+        let mut next = team.other();
+        self.record(
+            seat.clone(),
+            EventKind::TurnEnded {
+                team: team.clone(),
+                reason,
+            },
+        );
+
+        if let Some(turns_left) = self.turns_left.as_mut() {
+            *turns_left = turns_left.saturating_sub(1);
+            if *turns_left == 0 {
+                self.end_game(seat, None, EndReason::OutOfTurns);
+                return;
+            }
+
+            // A side with no agents left to find has nothing to give clues for
+            if self.agents_found(&next) {
+                next = team;
+            }
+        }
+
+        if self.skip_turn.as_ref() == Some(&next) {
+            self.skip_turn = None;
+            self.record(
+                Seat::operative(&next),
+                EventKind::TurnEnded {
+                    team: next.clone(),
+                    reason: TurnEndReason::Skipped,
+                },
+            );
+            next = next.other();
+        }
+
+        self.phase = Phase::Clue { team: next };
+        tracing::debug!("New Phase: {:?}", &self.phase);
+    }
+
+    fn end_game(&mut self, seat: Seat, winner: Option<Team>, reason: EndReason) {
+        self.phase = Phase::End {
+            winner: winner.clone(),
+            reason: reason.clone(),
+        };
+        self.record(seat, EventKind::GameEnded { winner, reason });
+        tracing::debug!("New Phase: {:?}", &self.phase);
+    }
+
+    /// Passes the turn, or loses the game when a team's chess clock runs out
+    fn time_out(&mut self) -> Result<(), GameError> {
+        let (team, seat) = match &self.phase {
+            Phase::Clue { team } => (team.clone(), Seat::spymaster(team)),
+            Phase::Guess { team, .. } => (team.clone(), Seat::operative(team)),
+            Phase::End { .. } => return Err(GameError::GameOver),
+        };
+
+        tracing::debug!("{} ran out of time", seat);
+        match self.config.timer() {
+            Some(TurnTimer::ChessClock { .. }) => {
+                self.end_game(seat, Some(team.other()), EndReason::Timeout)
+            }
+            _ => self.pass_turn(seat, team, TurnEndReason::Timeout),
+        }
+
+        Ok(())
+    }
+
+    /// Times out the current turn if its deadline has passed, returning whether it did
+    pub fn enforce_timer(&mut self, now: u64) -> bool {
+        match self.timer_status(now) {
+            Some(status) if status.remaining == 0 => self.time_out().is_ok(),
+            _ => false,
+        }
+    }
+
+    /// Restarts the timer from now, so time spent away from the game is not charged
+    pub fn resume_timer(&mut self) {
+        self.resumed_at = now_millis();
+    }
+
+    /// Starts the turn clocks, time spent waiting in the lobby is never charged
+    pub fn start_timer(&mut self) {
+        self.resume_timer();
+        self.started_at.get_or_insert(self.resumed_at);
+    }
+
+    /// When the first turn started
+    fn first_turn_at(&self) -> u64 {
+        self.started_at.unwrap_or(self.created_at)
+    }
+
+    pub fn timer_status(&self, now: u64) -> Option<TimerStatus> {
+        let team = match &self.phase {
+            Phase::Clue { team } | Phase::Guess { team, .. } => team,
+            Phase::End { .. } => return None,
+        };
+
+        let (deadline, clocks) = match self.config.timer()? {
+            TurnTimer::PerPhase { clue, guess } => {
+                let seconds = match self.phase {
+                    Phase::Clue { .. } => clue,
+                    _ => guess,
+                };
+                let started_at = self
+                    .events
+                    .iter()
+                    .rev()
+                    .find(|event| {
+                        matches!(
+                            event.kind(),
+                            EventKind::ClueGiven { .. } | EventKind::TurnEnded { .. }
+                        )
+                    })
+                    .map_or(self.first_turn_at(), GameEvent::timestamp)
+                    .max(self.resumed_at);
+
+                (started_at + *seconds as u64 * 1000, None)
+            }
+            TurnTimer::ChessClock { team: seconds } => {
+                let budget = *seconds as u64 * 1000;
+                let (mut red_used, mut blue_used) = (0, 0);
+                let mut turn_started_at = self.first_turn_at();
+                for event in &self.events {
+                    if let EventKind::TurnEnded { team, .. } = event.kind() {
+                        let spent = event.timestamp().saturating_sub(turn_started_at);
+                        match team {
+                            Team::Red => red_used += spent,
+                            Team::Blue => blue_used += spent,
+                        }
+                        turn_started_at = event.timestamp();
+                    }
+                }
+
+                let running = now.saturating_sub(turn_started_at.max(self.resumed_at));
+                match team {
+                    Team::Red => red_used += running,
+                    Team::Blue => blue_used += running,
+                }
+
+                let clocks = Clocks {
+                    red: budget.saturating_sub(red_used),
+                    blue: budget.saturating_sub(blue_used),
+                };
+                let remaining = match team {
+                    Team::Red => clocks.red,
+                    Team::Blue => clocks.blue,
+                };
 
-        let blue_win = self
-            .board
+                (now + remaining, Some(clocks))
+            }
+        };
+
+        Some(TimerStatus {
+            deadline,
+            remaining: deadline.saturating_sub(now),
+            clocks,
+        })
+    }
+
+    fn record(&mut self, seat: Seat, kind: EventKind) {
+        self.events.push(GameEvent::new(seat, kind));
+    }
+
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /// Seed the board was dealt from, hidden from players until the game ends
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Number of actions taken so far, every other event follows from one of them
+    pub fn steps(&self) -> usize {
+        self.actions().count()
+    }
+
+    fn actions(&self) -> impl Iterator<Item = &GameEvent> {
+        self.events.iter().filter(|event| event.kind().is_action())
+    }
+
+    /// Rebuilds the game by replaying its first `step` actions onto the board as it was dealt
+    pub fn at(&self, step: usize) -> Result<Self, GameError> {
+        if step > self.steps() {
+            return Err(GameError::StepOutOfRange {
+                step,
+                last: self.steps(),
+            });
+        }
+
+        let mut state = GameState {
+            board: self.board.iter().map(Card::dealt).collect(),
+            phase: Phase::Clue {
+                team: self.starting_team.clone(),
+            },
+            config: self.config.clone(),
+            starting_team: self.starting_team.clone(),
+            events: Vec::new(),
+            seed: self.seed,
+            turns_left: match self.config.mode() {
+                GameMode::Classic => None,
+                GameMode::Duet { turns } => Some(*turns),
+            },
+            skip_turn: None,
+            created_at: self.created_at,
+            started_at: self.started_at,
+            resumed_at: self.created_at,
+        };
+
+        for action in self.actions().take(step) {
+            state.apply(action)?;
+        }
+
+        // Replayed events happened when the original ones did
+        for (replayed, original) in state.events.iter_mut().zip(&self.events) {
+            replayed.set_timestamp(original.timestamp());
+        }
+
+        Ok(state)
+    }
+
+    fn apply(&mut self, action: &GameEvent) -> Result<(), GameError> {
+        match action.kind() {
+            EventKind::ClueGiven { word, count } => {
+                self.provide_clue(Clue::new(word.clone(), count.clone()))
+            }
+            EventKind::GuessMade { index, .. } => self.make_guess(GuessTarget::Index(*index)),
+            EventKind::TurnEnded {
+                reason: TurnEndReason::Timeout,
+                ..
+            }
+            | EventKind::GameEnded {
+                reason: EndReason::Timeout,
+                ..
+            } => self.time_out(),
+            EventKind::TurnEnded { .. } => self.end_turn(),
+            EventKind::GameEnded { .. } => self.forfeit(action.seat().clone()),
+        }
+    }
+
+    /// Checks that replaying every action reproduces this exact state
+    pub fn verify_replay(&self) -> Result<(), GameError> {
+        let replayed = self.at(self.steps())?;
+
+        if replayed.board != self.board {
+            return Err(GameError::ReplayMismatch("board"));
+        }
+
+        if replayed.phase != self.phase {
+            return Err(GameError::ReplayMismatch("phase"));
+        }
+
+        if replayed.events != self.events {
+            return Err(GameError::ReplayMismatch("events"));
+        }
+
+        Ok(())
+    }
+
+    /// Agents found minus assassin penalties, Duet has no scores
+    pub fn scores(&self) -> Option<Scores> {
+        if self.is_duet() {
+            return None;
+        }
+
+        Some(Scores {
+            red: self.score(&Team::Red),
+            blue: self.score(&Team::Blue),
+        })
+    }
+
+    fn score(&self, team: &Team) -> i32 {
+        let penalty = match self.config.house_rules().assassin() {
+            AssassinRule::InstantLoss => 0,
+            AssassinRule::Penalty { points } => *points as i32,
+        };
+
+        self.board
             .iter()
-            .filter(|card| card.identity == Identity::Blue)
-            .all(|card| card.guessed);
+            .filter(|card| card.guessed)
+            .map(|card| {
+                let guessed_by_team = card.guessed_by.as_ref() == Some(team);
+                match &card.identity {
+                    Identity::DoubleAgent if guessed_by_team => 1,
+                    Identity::Assassin if guessed_by_team => -penalty,
+                    identity if identity.team().as_ref() == Some(team) => 1,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
 
-        match (red_win, blue_win) {
-            (true, false) => Some(Team::Red),
-            (false, true) => Some(Team::Blue),
-            (true, true) => Some(Team::Red),
-            (false, false) => None,
+    pub fn winner(&self) -> Option<&Team> {
+        match &self.phase {
+            Phase::End { winner, .. } => winner.as_ref(),
+            _ => None,
         }
     }
 
@@ -299,20 +1019,56 @@ impl GameState {
         self.board
             .iter()
             .map(|card| Card {
-                word: card.word.clone(),
-                guessed: card.guessed,
                 identity: match card.guessed {
-                    true => card.identity.clone(),
+                    true => card.revealed_identity().clone(),
                     false => Identity::Hidden,
                 },
+                duet_identity: None,
+                ..card.clone()
+            })
+            .collect()
+    }
+
+    /// The board as a team's spymaster sees it, in Duet the other side's key stays hidden
+    pub fn to_side_board(&self, team: &Team) -> Vec<Card> {
+        self.board
+            .iter()
+            .map(|card| Card {
+                identity: match card.guessed {
+                    true => card.revealed_identity().clone(),
+                    false => card.key(team).clone(),
+                },
+                duet_identity: None,
+                ..card.clone()
             })
             .collect()
     }
 
     pub fn to_hidden_game_state(&self) -> Self {
+        self.with_board(self.to_hidden_board())
+    }
+
+    pub fn to_side_game_state(&self, team: &Team) -> Self {
+        self.with_board(self.to_side_board(team))
+    }
+
+    fn with_board(&self, board: Vec<Card>) -> Self {
         Self {
-            board: self.to_hidden_board(),
-            phase: self.phase.clone(),
+            board,
+            seed: match self.phase {
+                Phase::End { .. } => self.seed,
+                _ => None,
+            },
+            ..self.clone()
+        }
+    }
+
+    /// The seat expected to move next, None once the game is over
+    pub fn current_seat(&self) -> Option<Seat> {
+        match &self.phase {
+            Phase::Clue { team } => Some(Seat::spymaster(team)),
+            Phase::Guess { team, .. } => Some(Seat::operative(team)),
+            Phase::End { .. } => None,
         }
     }
 
@@ -326,4 +1082,586 @@ impl GameState {
     pub fn phase(&self) -> &Phase {
         &self.phase
     }
+
+    /// When the game ended, in milliseconds since the Unix epoch
+    pub fn ended_at(&self) -> Option<u64> {
+        match self.phase {
+            Phase::End { .. } => self.events.last().map(GameEvent::timestamp),
+            _ => None,
+        }
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn starting_team(&self) -> &Team {
+        &self.starting_team
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::game::{clue_validator::tests::legal_clue, word_bank::WordBank};
+
+    pub(crate) fn new_game() -> GameState {
+        GameState::new(config(json!({})), &WordBank::new(), 7)
+    }
+
+    pub(crate) fn config(house_rules: serde_json::Value) -> GameConfig {
+        serde_json::from_value(json!({
+            "rows": 5,
+            "columns": 5,
+            "startingAgents": 9,
+            "otherAgents": 8,
+            "bystanders": 7,
+            "assassins": 1,
+            "startingTeam": "Red",
+            "houseRules": house_rules,
+        }))
+        .unwrap()
+    }
+
+    /// Words on cards of the given identity, or of a team's agents
+    pub(crate) fn words<T>(game_state: &GameState, identity: T) -> Vec<String>
+    where
+        Identity: PartialEq<T>,
+    {
+        game_state
+            .board()
+            .iter()
+            .filter(|card| *card.identity() == identity)
+            .map(|card| card.word().to_string())
+            .collect()
+    }
+
+    pub(crate) fn give_clue(game_state: &mut GameState, count: ClueCount) {
+        let word = legal_clue(game_state.board()).to_string();
+        game_state.provide_clue(Clue::new(word, count)).unwrap();
+    }
+
+    pub(crate) fn guess(game_state: &mut GameState, word: &str) {
+        game_state
+            .make_guess(GuessTarget::Word(word.to_string()))
+            .unwrap();
+    }
+
+    #[test]
+    fn operatives_must_guess_before_ending_their_turn() {
+        let mut game_state = new_game();
+        let red = words(&game_state, Identity::Red);
+        assert!(matches!(game_state.end_turn(), Err(GameError::WrongPhase)));
+
+        give_clue(&mut game_state, ClueCount::Number(2));
+        assert!(matches!(
+            game_state.end_turn(),
+            Err(GameError::NoGuessesMade)
+        ));
+
+        guess(&mut game_state, &red[0]);
+        game_state.end_turn().unwrap();
+        assert!(matches!(
+            game_state.phase(),
+            Phase::Clue { team: Team::Blue }
+        ));
+    }
+
+    #[test]
+    fn number_clues_allow_one_bonus_guess() {
+        let mut game_state = new_game();
+        let red = words(&game_state, Identity::Red);
+
+        give_clue(&mut game_state, ClueCount::Number(2));
+        for word in &red[..2] {
+            guess(&mut game_state, word);
+            assert!(matches!(game_state.phase(), Phase::Guess { .. }));
+        }
+        assert_eq!(game_state.clue().unwrap().remaining(), Some(1));
+
+        guess(&mut game_state, &red[2]);
+        assert!(matches!(
+            game_state.phase(),
+            Phase::Clue { team: Team::Blue }
+        ));
+    }
+
+    #[test]
+    fn zero_and_unlimited_clues_allow_any_number_of_guesses() {
+        for count in [ClueCount::Zero, ClueCount::Unlimited] {
+            let mut game_state = new_game();
+            let red = words(&game_state, Identity::Red);
+
+            give_clue(&mut game_state, count);
+            for word in &red[..8] {
+                guess(&mut game_state, word);
+            }
+            assert!(matches!(game_state.phase(), Phase::Guess { .. }));
+            assert_eq!(game_state.clue().unwrap().remaining(), None);
+        }
+    }
+
+    #[test]
+    fn clue_counts_cannot_exceed_the_agents_left() {
+        let mut game_state = new_game();
+        let red = words(&game_state, Identity::Red);
+        let word = legal_clue(game_state.board()).to_string();
+        let numbered = |count| Clue::new(word.clone(), ClueCount::Number(count));
+
+        assert!(matches!(
+            game_state.provide_clue(numbered(10)),
+            Err(GameError::ClueCountTooHigh)
+        ));
+
+        give_clue(&mut game_state, ClueCount::Unlimited);
+        for word in &red[..3] {
+            guess(&mut game_state, word);
+        }
+        game_state.end_turn().unwrap();
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &red[3]);
+
+        // Red has five agents left, one of them revealed by Blue
+        assert!(matches!(
+            game_state.provide_clue(numbered(6)),
+            Err(GameError::ClueCountTooHigh)
+        ));
+        assert!(game_state.provide_clue(numbered(5)).is_ok());
+    }
+
+    #[test]
+    fn guesses_name_cards_by_word_index_or_position() {
+        let game_state = new_game();
+        let word = game_state.board()[7].word().to_string();
+        let index = |target| game_state.card_index(&target);
+
+        assert_eq!(index(GuessTarget::Word(word.to_uppercase())), Some(7));
+        assert_eq!(index(GuessTarget::Word(format!("  {}\t", word))), Some(7));
+        assert_eq!(index(GuessTarget::Word("quixotic".to_string())), None);
+
+        assert_eq!(index(GuessTarget::Index(24)), Some(24));
+        assert_eq!(index(GuessTarget::Index(25)), None);
+
+        assert_eq!(index(GuessTarget::Position { row: 1, column: 2 }), Some(7));
+        assert_eq!(index(GuessTarget::Position { row: 5, column: 0 }), None);
+        // Would wrap onto the next row if only the index were checked
+        assert_eq!(index(GuessTarget::Position { row: 0, column: 5 }), None);
+    }
+
+    #[test]
+    fn replaying_every_step_rebuilds_the_game() {
+        let mut game_state = new_game();
+        let red = words(&game_state, Identity::Red);
+        let blue = words(&game_state, Identity::Blue);
+        let bystander = words(&game_state, Identity::Bystander).remove(0);
+
+        // Games are compared through their JSON, which holds everything a game is made of
+        let snapshot = |game_state: &GameState| serde_json::to_value(game_state).unwrap();
+        let mut snapshots = vec![snapshot(&game_state)];
+        let mut play = |game_state: &mut GameState, action: &dyn Fn(&mut GameState)| {
+            action(game_state);
+            snapshots.push(snapshot(game_state));
+        };
+
+        play(&mut game_state, &|game_state| {
+            give_clue(game_state, ClueCount::Number(2))
+        });
+        play(&mut game_state, &|game_state| guess(game_state, &red[0]));
+        play(&mut game_state, &|game_state| guess(game_state, &bystander));
+        play(&mut game_state, &|game_state| {
+            give_clue(game_state, ClueCount::Unlimited)
+        });
+        for word in &blue[..3] {
+            play(&mut game_state, &|game_state| guess(game_state, word));
+        }
+        play(&mut game_state, &|game_state| {
+            game_state.end_turn().unwrap()
+        });
+        play(&mut game_state, &|game_state| {
+            give_clue(game_state, ClueCount::Zero)
+        });
+        for word in &red[1..] {
+            play(&mut game_state, &|game_state| guess(game_state, word));
+        }
+        assert_eq!(game_state.winner(), Some(&Team::Red));
+
+        assert_eq!(game_state.steps(), snapshots.len() - 1);
+        for (step, expected) in snapshots.iter().enumerate() {
+            assert_eq!(&snapshot(&game_state.at(step).unwrap()), expected);
+        }
+        assert_eq!(
+            snapshot(&game_state.at(game_state.steps()).unwrap()),
+            snapshot(&game_state)
+        );
+        assert!(game_state.verify_replay().is_ok());
+        assert!(matches!(
+            game_state.at(game_state.steps() + 1),
+            Err(GameError::StepOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn the_same_seed_deals_the_same_board() {
+        let word_bank = WordBank::new();
+        let deal = |seed| GameState::new(GameConfig::default(), &word_bank, seed);
+
+        let (game_state, again) = (deal(42), deal(42));
+        assert_eq!(game_state.board(), again.board());
+        assert_eq!(game_state.starting_team(), again.starting_team());
+        assert_eq!(game_state.seed, Some(42));
+
+        assert_ne!(game_state.board(), deal(43).board());
+    }
+
+    pub(crate) fn duet_game(turns: u8) -> GameState {
+        let config = serde_json::from_value(json!({
+            "rows": 5,
+            "columns": 5,
+            "startingAgents": 9,
+            "otherAgents": 8,
+            "bystanders": 7,
+            "assassins": 1,
+            "startingTeam": "Red",
+            "mode": { "type": "Duet", "turns": turns },
+        }))
+        .unwrap();
+        GameState::new(config, &WordBank::new(), 7)
+    }
+
+    /// Words showing `red_side` on the Red key and `blue_side` on the Blue key
+    fn duet_words(game_state: &GameState, red_side: Identity, blue_side: Identity) -> Vec<String> {
+        game_state
+            .board()
+            .iter()
+            .filter(|card| card.key(&Team::Red) == &red_side && card.key(&Team::Blue) == &blue_side)
+            .map(|card| card.word().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn duet_bystanders_only_cover_the_card_for_the_side_that_guessed_it() {
+        let mut game_state = duet_game(9);
+        let blue_agent = duet_words(&game_state, Identity::Bystander, Identity::Blue).remove(0);
+        let red_agent = duet_words(&game_state, Identity::Red, Identity::Bystander).remove(0);
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &blue_agent);
+        let card = game_state
+            .board()
+            .iter()
+            .find(|card| card.word() == blue_agent)
+            .unwrap();
+        assert!(!card.guessed());
+        assert_eq!(card.bystander_for(), &[Team::Red]);
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &red_agent);
+
+        // Red has seen the bystander on its key, but Blue's bystander is Red's agent
+        give_clue(&mut game_state, ClueCount::Number(1));
+        assert!(matches!(
+            game_state.make_guess(GuessTarget::Word(blue_agent.clone())),
+            Err(GameError::CardAlreadyGuessed)
+        ));
+        guess(&mut game_state, &red_agent);
+        assert!(game_state
+            .board()
+            .iter()
+            .any(|card| card.word() == red_agent && card.guessed()));
+        game_state.end_turn().unwrap();
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &blue_agent);
+        assert!(game_state
+            .board()
+            .iter()
+            .any(|card| card.word() == blue_agent && card.guessed()));
+        assert!(game_state.verify_replay().is_ok());
+    }
+
+    #[test]
+    fn duet_ends_when_the_turns_run_out() {
+        let mut game_state = duet_game(2);
+        let bystanders = duet_words(&game_state, Identity::Bystander, Identity::Bystander);
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &bystanders[0]);
+        assert!(matches!(
+            game_state.phase(),
+            Phase::Clue { team: Team::Blue }
+        ));
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &bystanders[1]);
+        assert_eq!(
+            game_state.phase(),
+            &Phase::End {
+                winner: None,
+                reason: EndReason::OutOfTurns,
+            }
+        );
+    }
+
+    #[test]
+    fn duet_assassins_on_either_key_lose_for_both_sides() {
+        let mut game_state = duet_game(9);
+        let assassin = duet_words(&game_state, Identity::Assassin, Identity::Blue).remove(0);
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &assassin);
+        assert_eq!(
+            game_state.phase(),
+            &Phase::End {
+                winner: None,
+                reason: EndReason::AssassinRevealed,
+            }
+        );
+
+        // Red's agent is an assassin on the Blue key
+        let mut game_state = duet_game(9);
+        let bystander = duet_words(&game_state, Identity::Bystander, Identity::Bystander).remove(0);
+        let assassin = duet_words(&game_state, Identity::Red, Identity::Assassin).remove(0);
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &bystander);
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &assassin);
+        assert_eq!(
+            game_state.phase(),
+            &Phase::End {
+                winner: None,
+                reason: EndReason::AssassinRevealed,
+            }
+        );
+    }
+
+    #[test]
+    fn duet_is_won_together_once_all_fifteen_agents_are_found() {
+        let mut game_state = duet_game(9);
+        let red = words(&game_state, Identity::Red);
+        let blue: Vec<String> = game_state
+            .board()
+            .iter()
+            .filter(|card| card.key(&Team::Blue) == &Identity::Blue && !red.contains(&card.word))
+            .map(|card| card.word().to_string())
+            .collect();
+        assert_eq!(red.len() + blue.len(), 15);
+
+        give_clue(&mut game_state, ClueCount::Unlimited);
+        for word in &red {
+            guess(&mut game_state, word);
+        }
+        game_state.end_turn().unwrap();
+
+        give_clue(&mut game_state, ClueCount::Unlimited);
+        for word in &blue {
+            guess(&mut game_state, word);
+        }
+        assert_eq!(
+            game_state.phase(),
+            &Phase::End {
+                winner: None,
+                reason: EndReason::AllAgentsFound,
+            }
+        );
+        assert!(game_state.verify_replay().is_ok());
+    }
+
+    #[test]
+    fn assassin_penalty_can_outweigh_finding_every_agent() {
+        let mut game_state = GameState::new(
+            config(json!({ "assassin": { "type": "Penalty", "points": 5 } })),
+            &WordBank::new(),
+            7,
+        );
+        let red = words(&game_state, Identity::Red);
+        let blue = words(&game_state, Identity::Blue);
+        let assassin = words(&game_state, Identity::Assassin).remove(0);
+
+        // Red reveals the assassin, which only ends its turn
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &assassin);
+        assert_eq!(game_state.phase(), &Phase::Clue { team: Team::Blue });
+
+        give_clue(&mut game_state, ClueCount::Unlimited);
+        for word in &blue[..7] {
+            guess(&mut game_state, word);
+        }
+        game_state.end_turn().unwrap();
+
+        // Red finds every agent for 9 - 5 = 4 points, behind Blue's 7
+        give_clue(&mut game_state, ClueCount::Unlimited);
+        for word in &red {
+            guess(&mut game_state, word);
+        }
+        assert_eq!(
+            game_state.phase(),
+            &Phase::End {
+                winner: Some(Team::Blue),
+                reason: EndReason::AllAgentsFound,
+            }
+        );
+        assert_eq!(game_state.score(&Team::Red), 4);
+        assert_eq!(game_state.score(&Team::Blue), 7);
+    }
+
+    #[test]
+    fn finishing_first_breaks_a_tie_on_points() {
+        let mut game_state = GameState::new(
+            config(json!({ "assassin": { "type": "Penalty", "points": 1 } })),
+            &WordBank::new(),
+            7,
+        );
+        let red = words(&game_state, Identity::Red);
+        let blue = words(&game_state, Identity::Blue);
+        let assassin = words(&game_state, Identity::Assassin).remove(0);
+
+        give_clue(&mut game_state, ClueCount::Unlimited);
+        for word in &red[..7] {
+            guess(&mut game_state, word);
+        }
+        game_state.end_turn().unwrap();
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &assassin);
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &blue[0]);
+
+        // Blue finds its last agents for 8 - 1 = 7 points, level with Red
+        give_clue(&mut game_state, ClueCount::Unlimited);
+        for word in &blue[1..] {
+            guess(&mut game_state, word);
+        }
+        assert_eq!(game_state.score(&Team::Red), 7);
+        assert_eq!(game_state.score(&Team::Blue), 7);
+        assert_eq!(game_state.winner(), Some(&Team::Blue));
+    }
+
+    fn timed_game(timer: serde_json::Value) -> GameState {
+        let mut config = serde_json::to_value(config(json!({}))).unwrap();
+        config["timer"] = timer;
+        GameState::new(serde_json::from_value(config).unwrap(), &WordBank::new(), 7)
+    }
+
+    /// Moves everything that has happened so far back in time
+    fn age(game_state: &mut GameState, millis: u64) {
+        game_state.created_at -= millis;
+        game_state.resumed_at -= millis;
+        for event in &mut game_state.events {
+            event.set_timestamp(event.timestamp() - millis);
+        }
+    }
+
+    fn remaining(game_state: &GameState) -> u64 {
+        game_state.timer_status(now_millis()).unwrap().remaining
+    }
+
+    #[test]
+    fn per_phase_timeouts_pass_the_turn() {
+        let mut game_state = timed_game(json!({ "type": "PerPhase", "clue": 30, "guess": 60 }));
+        assert!((29_000..=30_000).contains(&remaining(&game_state)));
+
+        let now = now_millis();
+        assert!(!game_state.enforce_timer(now + 29_000));
+        assert!(game_state.enforce_timer(now + 31_000));
+        assert_eq!(game_state.phase(), &Phase::Clue { team: Team::Blue });
+        assert!(matches!(
+            game_state.events().last().unwrap().kind(),
+            EventKind::TurnEnded {
+                team: Team::Red,
+                reason: TurnEndReason::Timeout,
+            }
+        ));
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        assert!((59_000..=60_000).contains(&remaining(&game_state)));
+    }
+
+    #[test]
+    fn chess_clock_timeouts_lose_the_game() {
+        let mut game_state = timed_game(json!({ "type": "ChessClock", "team": 60 }));
+        let red = words(&game_state, Identity::Red);
+
+        // Red spends 20 seconds on its turn before passing
+        age(&mut game_state, 20_000);
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &red[0]);
+        game_state.end_turn().unwrap();
+        assert!((39_000..=40_000).contains(
+            &game_state
+                .timer_status(now_millis())
+                .unwrap()
+                .clocks
+                .unwrap()
+                .red
+        ));
+
+        let now = now_millis();
+        assert!(!game_state.enforce_timer(now + 59_000));
+        assert!(game_state.enforce_timer(now + 61_000));
+        assert_eq!(
+            game_state.phase(),
+            &Phase::End {
+                winner: Some(Team::Red),
+                reason: EndReason::Timeout,
+            }
+        );
+    }
+
+    #[test]
+    fn rewinding_restarts_the_clock_once_resumed() {
+        let mut game_state = timed_game(json!({ "type": "PerPhase", "clue": 30, "guess": 60 }));
+        give_clue(&mut game_state, ClueCount::Number(1));
+        age(&mut game_state, 50_000);
+        assert!(remaining(&game_state) <= 10_000);
+
+        let mut rewound = game_state.at(game_state.steps()).unwrap();
+        assert!(remaining(&rewound) <= 10_000);
+
+        rewound.resume_timer();
+        assert!((59_000..=60_000).contains(&remaining(&rewound)));
+    }
+
+    #[test]
+    fn time_before_the_game_starts_is_not_charged() {
+        for timer in [
+            json!({ "type": "PerPhase", "clue": 30, "guess": 60 }),
+            json!({ "type": "ChessClock", "team": 30 }),
+        ] {
+            let mut game_state = timed_game(timer);
+
+            // Players took 50 seconds to join
+            age(&mut game_state, 50_000);
+            game_state.start_timer();
+            assert!((29_000..=30_000).contains(&remaining(&game_state)));
+
+            let now = now_millis();
+            assert!(!game_state.enforce_timer(now + 29_000));
+            assert!(game_state.enforce_timer(now + 31_000));
+        }
+    }
+
+    #[test]
+    fn clues_out_of_turn_are_rejected_before_the_word_is_checked() {
+        let mut game_state = new_game();
+        let board_word = game_state.board()[0].word().to_string();
+        let clue = Clue::new(board_word, ClueCount::Number(1));
+        assert!(matches!(
+            game_state.check_clue(&clue),
+            Err(GameError::IllegalClue(_))
+        ));
+
+        give_clue(&mut game_state, ClueCount::Number(1));
+        assert!(matches!(
+            game_state.check_clue(&clue),
+            Err(GameError::WrongPhase)
+        ));
+
+        game_state.forfeit(Seat::RedOperative).unwrap();
+        assert!(matches!(
+            game_state.check_clue(&clue),
+            Err(GameError::GameOver)
+        ));
+    }
 }
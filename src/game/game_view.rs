@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+use super::game_state::{GameState, Seat, Team};
+
+/// Which board someone is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub enum View {
+    #[default]
+    Operative,
+    Spymaster,
+}
+
+/// What the host lets spectators see
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum SpectatorView {
+    Operative,
+    Spymaster,
+    /// Spectators switch between the two themselves
+    Choice,
+}
+
+/// Someone looking at the game
+#[derive(Debug, Clone, PartialEq)]
+pub enum Viewer {
+    Seats(Vec<Seat>),
+    Spectator(View),
+}
+
+/// How much of the key card a viewer may know
+#[derive(Debug, Clone, PartialEq)]
+enum KeyAccess {
+    Full,
+    /// One side of a Duet key card
+    Side(Team),
+    None,
+}
+
+impl Viewer {
+    fn key_access(&self, game_state: &GameState) -> KeyAccess {
+        // Anyone giving clues already knows their key, even if they also guess for the team
+        let spymaster = match self {
+            Viewer::Seats(seats) => seats.iter().find(|seat| seat.is_spymaster()),
+            Viewer::Spectator(View::Spymaster) => return KeyAccess::Full,
+            Viewer::Spectator(View::Operative) => return KeyAccess::None,
+        };
+
+        match spymaster {
+            Some(seat) if game_state.is_duet() => KeyAccess::Side(seat.team()),
+            Some(_) => KeyAccess::Full,
+            None => KeyAccess::None,
+        }
+    }
+
+    /// The board this viewer is shown
+    pub fn view(&self, game_state: &GameState) -> View {
+        match self.key_access(game_state) {
+            KeyAccess::None => View::Operative,
+            KeyAccess::Full | KeyAccess::Side(_) => View::Spymaster,
+        }
+    }
+
+    /// The game as this viewer may see it, unrevealed identities they do not know are hidden
+    pub fn project(&self, game_state: &GameState) -> GameState {
+        match self.key_access(game_state) {
+            KeyAccess::Full => game_state.clone(),
+            KeyAccess::Side(team) => game_state.to_side_game_state(&team),
+            KeyAccess::None => game_state.to_hidden_game_state(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::game::game_state::{
+        tests::{duet_game, give_clue, guess, new_game, words},
+        ClueCount, Identity,
+    };
+
+    /// Checks the JSON a viewer is sent, where hidden cards and a missing seed hide the key
+    fn assert_key_hidden(viewer: &Viewer, game_state: &GameState) {
+        let json = serde_json::to_value(viewer.project(game_state)).unwrap();
+        for card in json["board"].as_array().unwrap() {
+            if card["guessed"] == Value::Bool(false) {
+                assert_eq!(card["identity"], "Hidden", "{:?} saw {}", viewer, card);
+            }
+            assert!(card.get("duetIdentity").is_none());
+        }
+        assert!(json.get("seed").is_none());
+    }
+
+    #[test]
+    fn classic_operatives_only_see_revealed_cards() {
+        let mut game_state = new_game();
+        let red = words(&game_state, Identity::Red);
+        let bystander = words(&game_state, Identity::Bystander).remove(0);
+        give_clue(&mut game_state, ClueCount::Number(2));
+        guess(&mut game_state, &red[0]);
+        guess(&mut game_state, &bystander);
+
+        for viewer in [
+            Viewer::Seats(vec![Seat::RedOperative]),
+            Viewer::Seats(vec![Seat::BlueOperative]),
+            Viewer::Spectator(View::Operative),
+        ] {
+            assert_key_hidden(&viewer, &game_state);
+            let revealed = viewer.project(&game_state);
+            let shown = revealed
+                .board()
+                .iter()
+                .filter(|card| *card.identity() != Identity::Hidden);
+            assert_eq!(shown.count(), 2);
+        }
+
+        let spymaster = Viewer::Seats(vec![Seat::RedSpymaster, Seat::RedOperative]);
+        assert!(spymaster
+            .project(&game_state)
+            .board()
+            .iter()
+            .all(|card| *card.identity() != Identity::Hidden));
+    }
+
+    #[test]
+    fn the_seed_is_shown_once_the_game_is_over() {
+        let mut game_state = new_game();
+        game_state.forfeit(Seat::RedSpymaster).unwrap();
+
+        let json = serde_json::to_value(Viewer::Spectator(View::Operative).project(&game_state));
+        assert_eq!(json.unwrap()["seed"], 7);
+    }
+
+    #[test]
+    fn duet_players_never_see_the_other_side_of_the_key() {
+        let mut game_state = duet_game(9);
+        let blue_agent = game_state
+            .board()
+            .iter()
+            .find(|card| {
+                card.key(&Team::Red) == &Identity::Bystander
+                    && card.key(&Team::Blue) == &Identity::Blue
+            })
+            .map(|card| card.word().to_string())
+            .unwrap();
+        give_clue(&mut game_state, ClueCount::Number(1));
+        guess(&mut game_state, &blue_agent);
+
+        for viewer in [
+            Viewer::Seats(vec![Seat::RedOperative]),
+            Viewer::Seats(vec![Seat::BlueOperative]),
+            Viewer::Spectator(View::Operative),
+        ] {
+            assert_key_hidden(&viewer, &game_state);
+        }
+
+        // A spymaster sees their own side of the key and nothing of the other
+        let spymaster = Viewer::Seats(vec![Seat::RedSpymaster, Seat::BlueOperative]);
+        let projected = spymaster.project(&game_state);
+        let json = serde_json::to_value(&projected).unwrap();
+        assert!(json.get("seed").is_none());
+        for (card, seen) in game_state.board().iter().zip(projected.board()) {
+            assert_eq!(seen.identity(), card.key(&Team::Red));
+        }
+        assert!(json["board"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|card| card.get("duetIdentity").is_none()));
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// What happens when an operative reveals one of the board's assassins
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum AssassinRule {
+    #[default]
+    InstantLoss,
+    /// Every assassin revealed ends the turn and costs the team points instead, once a team
+    /// finds all its agents the higher score wins
+    Penalty { points: u8 },
+}
+
+/// Optional variants on the classic rules, all off by default
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct HouseRules {
+    #[serde(default)]
+    assassin: AssassinRule,
+    /// Revealing a bystander also costs the team its next turn
+    #[serde(rename = "bystanderPenalty", default)]
+    bystander_penalty: bool,
+    /// One of the starting team's agents works for both teams, as in two player games
+    #[serde(rename = "doubleAgent", default)]
+    double_agent: bool,
+}
+
+impl HouseRules {
+    pub fn validate(&self) -> Result<()> {
+        if self.assassin == (AssassinRule::Penalty { points: 0 }) {
+            bail!("An assassin penalty must cost at least one point");
+        }
+
+        Ok(())
+    }
+
+    pub fn assassin(&self) -> &AssassinRule {
+        &self.assassin
+    }
+
+    pub fn bystander_penalty(&self) -> bool {
+        self.bystander_penalty
+    }
+
+    pub fn double_agent(&self) -> bool {
+        self.double_agent
+    }
+
+    /// Rules the AI players should know about, one per line
+    pub fn describe(&self) -> Vec<String> {
+        let mut rules = Vec::new();
+
+        if let AssassinRule::Penalty { points } = self.assassin {
+            rules.push(format!(
+                "Revealing an assassin does not lose the game, it ends the turn and costs the team {} points. When a team finds all its agents the team with the higher score wins.",
+                points
+            ));
+        }
+
+        if self.bystander_penalty {
+            rules.push("Revealing a bystander also costs the team its next turn.".to_string());
+        }
+
+        if self.double_agent {
+            rules.push("The Double Agent card counts as an agent for both teams.".to_string());
+        }
+
+        rules
+    }
+}
@@ -1,4 +1,11 @@
-mod agent;
+pub mod agent;
+pub mod clue_validator;
+pub mod game_actor;
+pub mod game_config;
 pub mod game_controller;
+pub mod game_error;
+pub mod game_event;
 pub mod game_state;
+pub mod game_view;
+pub mod house_rules;
 pub mod word_bank;
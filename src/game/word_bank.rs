@@ -3,7 +3,7 @@ use std::{
     io::{self, BufRead},
 };
 
-use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{seq::IndexedRandom, Rng};
 
 pub struct WordBank {
     inner: Vec<String>,
@@ -19,8 +19,8 @@ impl WordBank {
         Self { inner: words }
     }
 
-    pub fn get_word_set(&self, count: usize) -> Vec<String> {
-        let random_words = self.inner.choose_multiple(&mut rand::rng(), count);
+    pub fn get_word_set(&self, count: usize, rng: &mut impl Rng) -> Vec<String> {
+        let random_words = self.inner.choose_multiple(rng, count);
         random_words.into_iter().cloned().collect()
     }
 }
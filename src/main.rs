@@ -1,30 +1,61 @@
-use std::{collections::HashMap, env, net::SocketAddr, sync::Arc};
+use std::{
+    collections::HashMap,
+    env,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use axum::{
-    http::{header::CONTENT_TYPE, Method},
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE},
+        Method,
+    },
     routing::{get, post},
     Router,
 };
-use game::{game_controller::GameController, word_bank::WordBank};
-use tokio::sync::RwLock;
+use game::{
+    agent::{embeddings::Embeddings, registry::AgentRegistry},
+    game_actor::GameHandle,
+    game_error::GameError,
+    word_bank::WordBank,
+};
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt};
 use uuid::Uuid;
 
+use crate::reaper::{reap_games, ReaperConfig, ReaperStats};
 use crate::routes::{
+    admin::{get_replay, get_stats, get_verify, post_rewind},
     clue::post_clue,
-    game::{get_game, post_game, post_game_start},
+    forfeit::post_forfeit,
+    game::{delete_game, get_game, post_game, post_game_start},
     guess::post_guess,
+    join::post_join,
+    pass::post_pass,
     root::get_root,
 };
 
 mod app_error;
 mod game;
+mod reaper;
 mod routes;
 
 pub struct GameEnvironment {
-    controllers: RwLock<HashMap<Uuid, GameController>>,
+    /// Only ever locked long enough to add a game or clone out its handle
+    games: RwLock<HashMap<Uuid, GameHandle>>,
     word_bank: WordBank,
+    agent_registry: AgentRegistry,
+    /// Admin routes are disabled when no token is set
+    admin_token: Option<String>,
+    reaper_stats: ReaperStats,
+}
+
+impl GameEnvironment {
+    fn game(&self, game_id: &Uuid) -> Result<GameHandle, GameError> {
+        let games = self.games.read().expect("Games lock poisoned");
+        games.get(game_id).cloned().ok_or(GameError::GameNotFound)
+    }
 }
 
 #[tokio::main]
@@ -38,11 +69,36 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let embeddings = env::var("EMBEDDINGS_PATH").ok().map(|path| {
+        tracing::debug!("Loading embeddings from {}", path);
+        Arc::new(Embeddings::load(&path).expect("Could not load the embeddings"))
+    });
+
+    let mut agent_registry = AgentRegistry::new(embeddings);
+    let default_agent = env::var("DEFAULT_AGENT").ok().or_else(|| {
+        env::var("OPENAI_API_KEY").is_err().then(|| {
+            tracing::warn!("No OPENAI_API_KEY found, seats default to the mock agent");
+            "mock".to_string()
+        })
+    });
+    if let Some(default_agent) = default_agent {
+        agent_registry
+            .set_default_agent(&default_agent)
+            .expect("DEFAULT_AGENT must name an agent that can play both roles");
+    }
+    tracing::debug!("Default agent: {}", agent_registry.default_agent());
+
     let game_env = Arc::new(GameEnvironment {
-        controllers: RwLock::new(HashMap::new()),
+        games: RwLock::new(HashMap::new()),
         word_bank: WordBank::new(),
+        agent_registry,
+        admin_token: env::var("ADMIN_TOKEN").ok(),
+        reaper_stats: ReaperStats::default(),
     });
 
+    tokio::spawn(enforce_timers(game_env.clone()));
+    tokio::spawn(reap_games(game_env.clone(), ReaperConfig::from_env()));
+
     let env = env::var("ENV").expect("No ENV=prod|dev environment variable found");
     tracing::debug!("env: {:?}", env);
     if (env != "prod") && (env != "dev") {
@@ -51,6 +107,7 @@ async fn main() {
 
     let openai_env = env::var("OPENAI_API_KEY");
     tracing::debug!("OPENAI_API_KEY exists: {:?}", openai_env.is_ok());
+    tracing::debug!("ADMIN_TOKEN exists: {:?}", game_env.admin_token.is_some());
 
     let origins = match env.as_str() {
         "dev" => ["https://localhost:5173".parse().unwrap()],
@@ -60,8 +117,8 @@ async fn main() {
 
     let cors = CorsLayer::new()
         .allow_origin(origins)
-        .allow_methods([Method::GET, Method::POST])
-        .allow_headers([CONTENT_TYPE]);
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([CONTENT_TYPE, AUTHORIZATION]);
 
     let host = match env.as_str() {
         "dev" => [127, 0, 0, 1],
@@ -80,17 +137,48 @@ async fn main() {
         .with_state(game_env.clone())
         .route("/game", post(post_game))
         .with_state(game_env.clone())
-        .route("/game/{id}", get(get_game))
+        .route("/game/{id}", get(get_game).delete(delete_game))
         .with_state(game_env.clone())
         .route("/game/start/{id}", post(post_game_start))
         .with_state(game_env.clone())
+        .route("/game/join/{id}", post(post_join))
+        .with_state(game_env.clone())
         .route("/guess/{id}", post(post_guess))
         .with_state(game_env.clone())
         .route("/clue/{id}", post(post_clue))
         .with_state(game_env.clone())
+        .route("/pass/{id}", post(post_pass))
+        .with_state(game_env.clone())
+        .route("/forfeit/{id}", post(post_forfeit))
+        .with_state(game_env.clone())
+        .route("/admin/replay/{id}/{step}", get(get_replay))
+        .with_state(game_env.clone())
+        .route("/admin/rewind/{id}", post(post_rewind))
+        .with_state(game_env.clone())
+        .route("/admin/verify/{id}", get(get_verify))
+        .with_state(game_env.clone())
+        .route("/admin/stats", get(get_stats))
+        .with_state(game_env.clone())
         .layer(cors);
 
     tracing::debug!("listening on {}", addr);
 
     axum::serve(listener, app).await.unwrap();
 }
+
+/// Checks every game's turn timer, handing timed out turns to the next seat
+async fn enforce_timers(game_env: Arc<GameEnvironment>) {
+    let mut interval = tokio::time::interval(Duration::from_millis(500));
+    loop {
+        interval.tick().await;
+
+        let games: Vec<GameHandle> = {
+            let games = game_env.games.read().expect("Games lock poisoned");
+            games.values().cloned().collect()
+        };
+
+        for game in games {
+            game.enforce_timer();
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    game::{
+        game_actor::{Eviction, Expiry, GameHandle},
+        game_event::now_millis,
+        game_state::GameState,
+    },
+    GameEnvironment,
+};
+
+/// Checked at least this often, or more often when a TTL is shorter
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_IDLE_TTL: u64 = 60 * 60;
+const DEFAULT_FINISHED_TTL: u64 = 10 * 60;
+
+/// How long games are kept around, read from the environment
+pub struct ReaperConfig {
+    /// Games nobody has used for this long are abandoned
+    idle_ttl: Duration,
+    /// Finished games stay this long so players can look over the board
+    finished_ttl: Duration,
+    /// Evicted games are written here as JSON when set
+    archive_dir: Option<PathBuf>,
+}
+
+impl ReaperConfig {
+    pub fn from_env() -> Self {
+        let seconds = |name: &str, default: u64| {
+            let seconds = env::var(name).map_or(default, |value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|_| panic!("{} must be a number of seconds", name))
+            });
+            Duration::from_secs(seconds)
+        };
+
+        let archive_dir = env::var("ARCHIVE_DIR").ok().map(PathBuf::from);
+        if let Some(archive_dir) = &archive_dir {
+            std::fs::create_dir_all(archive_dir).expect("Could not create the archive directory");
+        }
+
+        ReaperConfig {
+            idle_ttl: seconds("GAME_IDLE_TTL", DEFAULT_IDLE_TTL),
+            finished_ttl: seconds("GAME_FINISHED_TTL", DEFAULT_FINISHED_TTL),
+            archive_dir,
+        }
+    }
+}
+
+/// Running totals since the server started
+#[derive(Default)]
+pub struct ReaperStats {
+    evicted_idle: AtomicU64,
+    evicted_finished: AtomicU64,
+    archived: AtomicU64,
+    archive_failures: AtomicU64,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct ReaperStatsSnapshot {
+    evicted_idle: u64,
+    evicted_finished: u64,
+    archived: u64,
+    archive_failures: u64,
+}
+
+impl ReaperStats {
+    pub fn snapshot(&self) -> ReaperStatsSnapshot {
+        ReaperStatsSnapshot {
+            evicted_idle: self.evicted_idle.load(Ordering::Relaxed),
+            evicted_finished: self.evicted_finished.load(Ordering::Relaxed),
+            archived: self.archived.load(Ordering::Relaxed),
+            archive_failures: self.archive_failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Evicts abandoned and long finished games so memory does not grow with every game played
+pub async fn reap_games(game_env: Arc<GameEnvironment>, config: ReaperConfig) {
+    let period = REAP_INTERVAL
+        .min(config.idle_ttl)
+        .min(config.finished_ttl)
+        .max(Duration::from_secs(1));
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        let games: Vec<(Uuid, GameHandle)> = {
+            let games = game_env.games.read().expect("Games lock poisoned");
+            games
+                .iter()
+                .map(|(game_id, game)| (*game_id, game.clone()))
+                .collect()
+        };
+
+        let now = now_millis();
+        let expiry = Expiry {
+            idle_before: now.saturating_sub(config.idle_ttl.as_millis() as u64),
+            ended_before: now.saturating_sub(config.finished_ttl.as_millis() as u64),
+        };
+
+        let (mut idle, mut finished) = (0, 0);
+        for (game_id, game) in games {
+            // Already gone if the game was deleted in the meantime
+            let Ok(Some((eviction, game_state))) = game.expired(expiry).await else {
+                continue;
+            };
+
+            // A game that could not be archived is kept for the next pass to try again
+            if let Some(archive_dir) = &config.archive_dir {
+                if !archive(&game_env, archive_dir, game_id, &game_state).await {
+                    continue;
+                }
+            }
+
+            // Someone may have come back to the game while it was being archived
+            let events = game_state.events().len();
+            if !game.evict(expiry, events).await.unwrap_or(false) {
+                continue;
+            }
+
+            {
+                let mut games = game_env.games.write().expect("Games lock poisoned");
+                games.remove(&game_id);
+            }
+
+            match eviction {
+                Eviction::Idle => idle += 1,
+                Eviction::Finished => finished += 1,
+            }
+        }
+
+        let stats = &game_env.reaper_stats;
+        stats.evicted_idle.fetch_add(idle, Ordering::Relaxed);
+        stats
+            .evicted_finished
+            .fetch_add(finished, Ordering::Relaxed);
+
+        if idle + finished > 0 {
+            let left = game_env.games.read().expect("Games lock poisoned").len();
+            tracing::info!(
+                "Evicted {} idle and {} finished games, {} games left",
+                idle,
+                finished,
+                left
+            );
+        }
+    }
+}
+
+/// The whole game is kept, seed and events included, so it can be replayed later
+async fn archive(
+    game_env: &GameEnvironment,
+    archive_dir: &Path,
+    game_id: Uuid,
+    game_state: &GameState,
+) -> bool {
+    let path = archive_dir.join(format!("{}.json", game_id));
+    let result = match serde_json::to_vec(game_state) {
+        Ok(json) => tokio::fs::write(&path, json)
+            .await
+            .map_err(|err| err.to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+
+    match result {
+        Ok(()) => {
+            game_env
+                .reaper_stats
+                .archived
+                .fetch_add(1, Ordering::Relaxed);
+            tracing::debug!("Archived game {} to {}", game_id, path.display());
+            true
+        }
+        Err(err) => {
+            game_env
+                .reaper_stats
+                .archive_failures
+                .fetch_add(1, Ordering::Relaxed);
+            tracing::warn!("Could not archive game {}, keeping it: {}", game_id, err);
+            false
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_extra::TypedHeader;
+use axum_macros::debug_handler;
+use headers::{authorization::Bearer, Authorization};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::{
+    app_error::AppError,
+    game::{game_error::GameError, game_state::GameState},
+    reaper::ReaperStatsSnapshot,
+    GameEnvironment,
+};
+
+/// Compared in constant time so the token cannot be guessed from how long a rejection takes
+fn check_admin(game_env: &GameEnvironment, bearer: &Bearer) -> Result<(), AppError> {
+    match &game_env.admin_token {
+        Some(token) if bool::from(token.as_bytes().ct_eq(bearer.token().as_bytes())) => Ok(()),
+        _ => {
+            let err = GameError::NotAuthorized;
+            tracing::warn!("{}", err);
+            Err(err.into())
+        }
+    }
+}
+
+#[debug_handler]
+pub async fn get_replay(
+    Path((game_id, step)): Path<(Uuid, usize)>,
+    TypedHeader(Authorization(bearer)): TypedHeader<Authorization<Bearer>>,
+    State(game_env): State<Arc<GameEnvironment>>,
+) -> Result<Json<GameState>, AppError> {
+    tracing::info!("get_replay");
+    check_admin(&game_env, &bearer)?;
+
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    let game_state = game.game_state_at(step).await?;
+    Ok(Json(game_state))
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct PostRewindRequest {
+    step: usize,
+}
+
+#[debug_handler]
+pub async fn post_rewind(
+    Path(game_id): Path<Uuid>,
+    TypedHeader(Authorization(bearer)): TypedHeader<Authorization<Bearer>>,
+    State(game_env): State<Arc<GameEnvironment>>,
+    Json(payload): Json<PostRewindRequest>,
+) -> Result<(), AppError> {
+    tracing::info!("post_rewind");
+    check_admin(&game_env, &bearer)?;
+
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    game.rewind(payload.step).await?;
+    Ok(())
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct GetVerifyResponse {
+    steps: usize,
+}
+
+#[debug_handler]
+pub async fn get_verify(
+    Path(game_id): Path<Uuid>,
+    TypedHeader(Authorization(bearer)): TypedHeader<Authorization<Bearer>>,
+    State(game_env): State<Arc<GameEnvironment>>,
+) -> Result<Json<GetVerifyResponse>, AppError> {
+    tracing::info!("get_verify");
+    check_admin(&game_env, &bearer)?;
+
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    let steps = game.verify_replay().await.inspect_err(|err| {
+        tracing::warn!("Replay does not match the game: {}", err);
+    })?;
+    Ok(Json(GetVerifyResponse { steps }))
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct GetStatsResponse {
+    games: usize,
+    #[serde(flatten)]
+    reaper: ReaperStatsSnapshot,
+}
+
+#[debug_handler]
+pub async fn get_stats(
+    TypedHeader(Authorization(bearer)): TypedHeader<Authorization<Bearer>>,
+    State(game_env): State<Arc<GameEnvironment>>,
+) -> Result<Json<GetStatsResponse>, AppError> {
+    tracing::info!("get_stats");
+    check_admin(&game_env, &bearer)?;
+
+    let games = game_env.games.read().expect("Games lock poisoned").len();
+    let reaper = game_env.reaper_stats.snapshot();
+    Ok(Json(GetStatsResponse { games, reaper }))
+}
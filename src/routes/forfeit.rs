@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum_macros::debug_handler;
+use uuid::Uuid;
+
+use crate::{
+    app_error::AppError,
+    routes::{seat_token, SeatToken},
+    GameEnvironment,
+};
+
+#[debug_handler]
+pub async fn post_forfeit(
+    Path(game_id): Path<Uuid>,
+    bearer: SeatToken,
+    State(game_env): State<Arc<GameEnvironment>>,
+) -> Result<(), AppError> {
+    tracing::info!("post_forfeit");
+
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    game.player_forfeit(seat_token(&bearer))
+        .await
+        .map_err(|err| {
+            tracing::warn!("Could not forfeit the game: {}", err);
+            err.into()
+        })
+}
@@ -1,8 +1,7 @@
 use std::sync::Arc;
 
-use anyhow::{Error, Result};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
 use axum_extra::TypedHeader;
@@ -12,19 +11,43 @@ use uuid::Uuid;
 
 use crate::{
     app_error::AppError,
+    game::agent::SeatAgents,
+    game::game_actor::GameHandle,
+    game::game_config::{GameConfig, GameMode},
     game::game_controller::{GameController, Role},
-    game::game_state::GameState,
+    game::game_error::GameError,
+    game::game_state::{GameState, Scores, Seat, Team, TimerStatus},
+    game::game_view::{SpectatorView, View},
+    routes::{seat_token, SeatToken},
     GameEnvironment,
 };
 
+const MAX_GENERATED_SEED: u64 = 1 << 53;
+
 #[derive(Deserialize, Debug)]
 pub struct PostGameRequest {
     role: Role,
+    #[serde(default)]
+    config: GameConfig,
+    /// Reuse a seed to deal the same board again
+    #[serde(default)]
+    seed: Option<u64>,
+    /// Other players can join the seats left over until the game starts
+    #[serde(default)]
+    multiplayer: bool,
+    #[serde(rename = "spectatorView", default)]
+    spectator_view: Option<SpectatorView>,
+    /// Registered agents for the seats no human plays
+    #[serde(default)]
+    agents: SeatAgents,
 }
 
 #[derive(Serialize, Debug)]
 pub struct PostGameResponse {
     game_id: Uuid,
+    /// Token for the creator's seats in a multiplayer game
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
 }
 
 #[debug_handler]
@@ -34,16 +57,57 @@ pub async fn post_game(
 ) -> Result<Json<PostGameResponse>, AppError> {
     tracing::info!("post_game");
 
-    let game_id = Uuid::new_v4();
-    let words = game_env.word_bank.get_word_set(25);
-    let controller = GameController::new(payload.role, words);
+    if let Err(err) = payload.config.validate() {
+        tracing::warn!("{}", err);
+        return Err(GameError::InvalidConfig(err.to_string()).into());
+    }
 
+    if payload.multiplayer && payload.config.mode() != &GameMode::Classic {
+        let err = GameError::InvalidConfig("Only classic games can be multiplayer".to_string());
+        tracing::warn!("{}", err);
+        return Err(err.into());
+    }
+
+    // Operatives without a token could otherwise read the key card as spectators
+    if payload.multiplayer
+        && matches!(
+            payload.spectator_view,
+            Some(SpectatorView::Spymaster | SpectatorView::Choice)
+        )
     {
-        let mut controllers = game_env.controllers.write().await;
-        controllers.entry(game_id).or_insert(controller);
+        let err = GameError::InvalidConfig(
+            "Spectators of multiplayer games can only see the operative board".to_string(),
+        );
+        tracing::warn!("{}", err);
+        return Err(err.into());
     }
 
-    Ok(Json(PostGameResponse { game_id }))
+    let game_id = Uuid::new_v4();
+    // Generated seeds stay within what a JavaScript number holds exactly
+    let seed = payload
+        .seed
+        .unwrap_or_else(|| rand::random_range(0..MAX_GENERATED_SEED));
+    let game_state = GameState::new(payload.config, &game_env.word_bank, seed);
+    let mut controller = GameController::new(
+        payload.role.clone(),
+        game_state,
+        payload.multiplayer,
+        payload.spectator_view,
+        &game_env.agent_registry,
+        &payload.agents,
+    )
+    .inspect_err(|err| tracing::warn!("{}", err))?;
+
+    let token = match (payload.multiplayer, payload.role) {
+        (true, Role::Spectator) | (false, _) => None,
+        (true, role) => Some(controller.join(role)?),
+    };
+
+    let mut games = game_env.games.write().expect("Games lock poisoned");
+    games.insert(game_id, GameHandle::spawn(controller));
+    drop(games);
+
+    Ok(Json(PostGameResponse { game_id, token }))
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -53,41 +117,79 @@ pub enum GetGameResponse {
         #[serde(rename = "gameState")]
         game_state: GameState,
         role: Role,
+        #[serde(rename = "startingTeam")]
+        starting_team: Team,
+        winner: Option<Team>,
+        scores: Option<Scores>,
+        timer: Option<TimerStatus>,
+        view: View,
+        /// Seats the requester plays
+        seats: Vec<Seat>,
+        /// The AI is working on its next move
+        stepping: bool,
+        /// Why the AI's last move failed, until it makes one
+        #[serde(rename = "agentError")]
+        agent_error: Option<String>,
     },
 }
 
+#[derive(Deserialize, Debug)]
+pub struct GetGameQuery {
+    /// Spectators can switch between the operative and spymaster boards if the host lets them
+    #[serde(default)]
+    view: View,
+}
+
 pub async fn get_game(
     Path(game_id): Path<Uuid>,
+    Query(query): Query<GetGameQuery>,
+    bearer: SeatToken,
     TypedHeader(user_agent): TypedHeader<headers::UserAgent>,
     State(game_env): State<Arc<GameEnvironment>>,
 ) -> Result<Json<GetGameResponse>, AppError> {
     tracing::info!("get_game: {:?}", user_agent);
 
-    let controllers = game_env.controllers.read().await;
-    if let Some(controller) = controllers.get(&game_id) {
-        let game_data: GetGameResponse = controller.game_data().await;
-        return Ok(Json(game_data));
-    }
-
-    let err = Error::msg("Could not find the game");
-    tracing::warn!("{}", err);
-    Err(AppError(err))
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    let game_data = game.game_data(seat_token(&bearer), query.view).await?;
+    Ok(Json(game_data))
 }
 
 #[debug_handler]
 pub async fn post_game_start(
     Path(game_id): Path<Uuid>,
+    bearer: SeatToken,
     State(game_env): State<Arc<GameEnvironment>>,
 ) -> Result<(), AppError> {
     tracing::info!("post_game_start");
 
-    let controllers = game_env.controllers.read().await;
-    if let Some(controller) = controllers.get(&game_id) {
-        controller.step_until_input().await;
-        return Ok(());
-    }
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    game.start(seat_token(&bearer)).await.map_err(|err| {
+        tracing::warn!("Could not step the game: {}", err);
+        err.into()
+    })
+}
+
+#[debug_handler]
+pub async fn delete_game(
+    Path(game_id): Path<Uuid>,
+    bearer: SeatToken,
+    State(game_env): State<Arc<GameEnvironment>>,
+) -> Result<(), AppError> {
+    tracing::info!("delete_game");
+
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    game.delete(seat_token(&bearer)).await.map_err(|err| {
+        tracing::warn!("Could not delete the game: {}", err);
+        AppError::from(err)
+    })?;
 
-    let err = Error::msg("Could not find the game");
-    tracing::warn!("{}", err);
-    Err(AppError(err))
+    let mut games = game_env.games.write().expect("Games lock poisoned");
+    games.remove(&game_id);
+    Ok(())
 }
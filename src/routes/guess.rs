@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use anyhow::Error;
 use axum::{
     extract::{Path, State},
     Json,
@@ -9,43 +8,47 @@ use axum_macros::debug_handler;
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::{app_error::AppError, GameEnvironment};
+use crate::{
+    app_error::AppError,
+    game::game_state::GuessTarget,
+    routes::{seat_token, SeatToken},
+    GameEnvironment,
+};
 
 #[derive(Clone, Deserialize, Debug)]
-pub struct PostGuessRequest {
-    guess: String,
+#[serde(untagged)]
+pub enum PostGuessRequest {
+    Word { guess: String },
+    Index { index: usize },
+    Position { row: usize, column: usize },
+}
+
+impl From<PostGuessRequest> for GuessTarget {
+    fn from(request: PostGuessRequest) -> Self {
+        match request {
+            PostGuessRequest::Word { guess } => GuessTarget::Word(guess),
+            PostGuessRequest::Index { index } => GuessTarget::Index(index),
+            PostGuessRequest::Position { row, column } => GuessTarget::Position { row, column },
+        }
+    }
 }
 
 #[debug_handler]
 pub async fn post_guess(
     Path(game_id): Path<Uuid>,
+    bearer: SeatToken,
     State(game_env): State<Arc<GameEnvironment>>,
     Json(payload): Json<PostGuessRequest>,
 ) -> Result<(), AppError> {
     tracing::info!("post_guess");
 
-    let game_env_clone = game_env.clone();
-    let controllers = game_env.controllers.read().await;
-    if let Some(controller) = controllers.get(&game_id) {
-        let res = controller.player_guess(payload.guess).await;
-
-        if res.is_some() {
-            tokio::spawn(async move {
-                let controllers = game_env_clone.controllers.read().await;
-                if let Some(controller) = controllers.get(&game_id) {
-                    controller.step_until_input().await;
-                }
-            });
-        }
-
-        return res.ok_or_else(|| {
-            let err = Error::msg("Could not make guess");
-            tracing::warn!("{}", err);
-            AppError(err)
-        });
-    }
-
-    let err = Error::msg("Could not find the game");
-    tracing::warn!("{}", err);
-    Err(AppError(err))
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    game.player_guess(seat_token(&bearer), payload.into())
+        .await
+        .map_err(|err| {
+            tracing::warn!("Could not make guess: {}", err);
+            err.into()
+        })
 }
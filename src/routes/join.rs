@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_macros::debug_handler;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{app_error::AppError, game::game_controller::Role, GameEnvironment};
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct PostJoinRequest {
+    role: Role,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct PostJoinResponse {
+    /// Sent as a bearer token with every move made from the claimed seats
+    token: String,
+}
+
+#[debug_handler]
+pub async fn post_join(
+    Path(game_id): Path<Uuid>,
+    State(game_env): State<Arc<GameEnvironment>>,
+    Json(payload): Json<PostJoinRequest>,
+) -> Result<Json<PostJoinResponse>, AppError> {
+    tracing::info!("post_join");
+
+    let game = game_env
+        .game(&game_id)
+        .inspect_err(|err| tracing::warn!("{}", err))?;
+    let token = game.join(payload.role).await.map_err(|err| {
+        tracing::warn!("Could not join the game: {}", err);
+        AppError::from(err)
+    })?;
+
+    Ok(Json(PostJoinResponse { token }))
+}
@@ -1,4 +1,20 @@
+use axum_extra::TypedHeader;
+use headers::{authorization::Bearer, Authorization};
+
+pub mod admin;
 pub mod clue;
+pub mod forfeit;
 pub mod game;
 pub mod guess;
+pub mod join;
+pub mod pass;
 pub mod root;
+
+/// Multiplayer clients send the token they got for their seats, single player games need none
+pub type SeatToken = Option<TypedHeader<Authorization<Bearer>>>;
+
+pub fn seat_token(header: &SeatToken) -> Option<&str> {
+    header
+        .as_ref()
+        .map(|TypedHeader(Authorization(bearer))| bearer.token())
+}